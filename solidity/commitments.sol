@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.23;
+
+// keeps commitments at the storage keys of the IBC v2 handler, which the caller derives
+// from the path, so proofs can be tested without deploying the full handler
+contract Commitments {
+    function commit(bytes32 key, bytes32 commitment) public {
+        assembly {
+            sstore(key, commitment)
+        }
+    }
+
+    function commitments(bytes32 key) public view returns (bytes32 commitment) {
+        assembly {
+            commitment := sload(key)
+        }
+    }
+}
@@ -0,0 +1,526 @@
+use core::marker::PhantomData;
+use core::time::Duration;
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use anyhow::Context;
+use bon::Builder;
+use prost::Message;
+use protos::google::protobuf::Any;
+use protos::ibc::core::client::v1::{MsgCreateClient, MsgSubmitMisbehaviour, MsgUpdateClient};
+use protos::ibc::lightclients::wasm::v1::{
+    ClientMessage as WasmClientMessage, ClientState as WasmClientState,
+    ConsensusState as WasmConsensusState,
+};
+use protos::union::ibc::lightclients::ethereum::v1::{
+    ClientState as ClientStateProto, ConsensusState as ConsensusStateProto, Header as HeaderProto,
+    Misbehaviour as MisbehaviourProto,
+};
+use unionlabs::ethereum::config::{
+    BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES, SYNC_COMMITTEE_SIZE,
+};
+use unionlabs::ibc::core::client::height::Height;
+use unionlabs::ibc::lightclients::ethereum::client_state::ClientState;
+use unionlabs::ibc::lightclients::ethereum::consensus_state::ConsensusState;
+use unionlabs::ibc::lightclients::ethereum::header::Header;
+use unionlabs::ibc::lightclients::ethereum::light_client_header::UnboundedLightClientHeader;
+use unionlabs::ibc::lightclients::ethereum::misbehaviour::Misbehaviour;
+
+use crate::cosmos::{
+    msg_update_client, MSG_CREATE_CLIENT_TYPE_URL, MSG_SUBMIT_MISBEHAVIOUR_TYPE_URL,
+    MSG_UPDATE_CLIENT_TYPE_URL,
+};
+use crate::relayer::daemon::Counterparty;
+use crate::relayer::optimistic::OptimisticHeader;
+
+pub const CLIENT_TYPE: &str = "08-wasm";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientStatus {
+    Active,
+    Expired,
+    Frozen,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockResponse {
+    ClientCreated {
+        client_id: String,
+        height: u64,
+    },
+    ClientUpdated {
+        client_id: String,
+        height: u64,
+    },
+    ClientFrozen {
+        client_id: String,
+        height: u64,
+    },
+    // `reorged` when a different header was already tracked at the same slot
+    OptimisticUpdated {
+        client_id: String,
+        height: u64,
+        reorged: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct MockClient {
+    pub checksum: Vec<u8>,
+    pub client_state: ClientState,
+    pub consensus_states: BTreeMap<u64, ConsensusState>,
+    pub optimistic_heads: OptimisticHeads,
+}
+
+impl MockClient {
+    pub fn latest_height(&self) -> u64 {
+        self.client_state.latest_slot
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.client_state.frozen_height.revision_height != 0
+    }
+
+    pub fn latest_consensus_state(&self) -> Option<&ConsensusState> {
+        self.consensus_states.get(&self.latest_height())
+    }
+}
+
+// attested headers above the finalized height, one per slot
+// a later header for a slot replaces the earlier one, as a reorg would, and finality drops
+// everything at or below the finalized height
+#[derive(Debug, Clone, Default)]
+pub struct OptimisticHeads(BTreeMap<u64, UnboundedLightClientHeader>);
+
+impl OptimisticHeads {
+    // returns the header it replaced
+    pub fn insert(
+        &mut self,
+        finalized_height: u64,
+        header: UnboundedLightClientHeader,
+    ) -> anyhow::Result<Option<UnboundedLightClientHeader>> {
+        let slot = header.beacon.slot;
+
+        anyhow::ensure!(
+            slot > finalized_height,
+            "optimistic slot {} is not above the finalized height {}",
+            slot,
+            finalized_height,
+        );
+
+        Ok(self.0.insert(slot, header))
+    }
+
+    pub fn finalize(&mut self, finalized_height: u64) {
+        self.0 = self.0.split_off(&(finalized_height + 1));
+    }
+
+    pub fn get(&self, slot: u64) -> Option<&UnboundedLightClientHeader> {
+        self.0.get(&slot)
+    }
+
+    pub fn latest(&self) -> Option<&UnboundedLightClientHeader> {
+        self.0.values().next_back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+// in-memory stand-in for a cosmos chain's 08-wasm client store
+// it does not verify signatures or proofs, only the bookkeeping around them
+#[derive(Builder, Debug)]
+pub struct MockCounterparty<C> {
+    #[builder(default = Duration::from_secs(14 * 24 * 60 * 60))]
+    pub trusting_period: Duration,
+    // unix time in nanos; the system clock is used when unset
+    pub now: Option<u64>,
+    #[builder(default)]
+    pub clients: BTreeMap<String, MockClient>,
+    #[builder(default)]
+    pub _phantom: PhantomData<C>,
+}
+
+impl<C> Default for MockCounterparty<C> {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES>
+    MockCounterparty<C>
+{
+    pub fn now(&self) -> u64 {
+        self.now.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .expect("system clock before unix epoch")
+                .as_nanos()
+                .try_into()
+                .expect("nanos overflow u64")
+        })
+    }
+
+    pub fn client(&self, client_id: &str) -> anyhow::Result<&MockClient> {
+        self.clients
+            .get(client_id)
+            .with_context(|| format!("client {client_id} not found"))
+    }
+
+    pub fn status(&self, client_id: &str) -> anyhow::Result<ClientStatus> {
+        let client = self.client(client_id)?;
+
+        if client.is_frozen() {
+            return Ok(ClientStatus::Frozen);
+        }
+
+        let latest = client
+            .latest_consensus_state()
+            .context("latest consensus state")?;
+
+        if self.is_expired(latest) {
+            Ok(ClientStatus::Expired)
+        } else {
+            Ok(ClientStatus::Active)
+        }
+    }
+
+    pub fn is_expired(&self, consensus_state: &ConsensusState) -> bool {
+        let trusting_period: u64 = self
+            .trusting_period
+            .as_nanos()
+            .try_into()
+            .expect("trusting period overflows u64");
+
+        consensus_state.timestamp.saturating_add(trusting_period) <= self.now()
+    }
+
+    #[allow(deprecated)]
+    pub fn handle(&mut self, msg: Any) -> anyhow::Result<MockResponse> {
+        match msg.type_url.as_str() {
+            MSG_CREATE_CLIENT_TYPE_URL => {
+                self.create_client(MsgCreateClient::decode(msg.value.as_ref())?)
+            }
+            MSG_UPDATE_CLIENT_TYPE_URL => {
+                self.update_client(MsgUpdateClient::decode(msg.value.as_ref())?)
+            }
+            MSG_SUBMIT_MISBEHAVIOUR_TYPE_URL => {
+                self.submit_misbehaviour(MsgSubmitMisbehaviour::decode(msg.value.as_ref())?)
+            }
+            type_url => anyhow::bail!("unsupported message type {type_url}"),
+        }
+    }
+
+    pub fn create_client(&mut self, msg: MsgCreateClient) -> anyhow::Result<MockResponse> {
+        let wasm_client_state = WasmClientState::decode(
+            msg.client_state
+                .context("missing client state")?
+                .value
+                .as_ref(),
+        )?;
+        let wasm_consensus_state = WasmConsensusState::decode(
+            msg.consensus_state
+                .context("missing consensus state")?
+                .value
+                .as_ref(),
+        )?;
+
+        let client_state: ClientState =
+            ClientStateProto::decode(wasm_client_state.data.as_slice())?.try_into()?;
+        let consensus_state: ConsensusState =
+            ConsensusStateProto::decode(wasm_consensus_state.data.as_slice())?.try_into()?;
+
+        let height = client_state.latest_slot;
+
+        anyhow::ensure!(
+            consensus_state.slot == height,
+            "consensus state slot {} does not match client height {}",
+            consensus_state.slot,
+            height,
+        );
+        anyhow::ensure!(
+            client_state.frozen_height.revision_height == 0,
+            "cannot create a frozen client",
+        );
+        anyhow::ensure!(
+            !self.is_expired(&consensus_state),
+            "consensus state is already outside of the trusting period",
+        );
+
+        let client_id = format!("{}-{}", CLIENT_TYPE, self.clients.len());
+
+        self.clients.insert(
+            client_id.clone(),
+            MockClient {
+                checksum: wasm_client_state.checksum,
+                client_state,
+                consensus_states: BTreeMap::from([(height, consensus_state)]),
+                optimistic_heads: OptimisticHeads::default(),
+            },
+        );
+
+        Ok(MockResponse::ClientCreated { client_id, height })
+    }
+
+    pub fn update_client(&mut self, msg: MsgUpdateClient) -> anyhow::Result<MockResponse> {
+        let client_message = WasmClientMessage::decode(
+            msg.client_message
+                .context("missing client message")?
+                .value
+                .as_ref(),
+        )?;
+
+        let header: Header<C> = HeaderProto::decode(client_message.data.as_slice())?.try_into()?;
+
+        self.apply_header(&msg.client_id, header)
+    }
+
+    #[allow(deprecated)]
+    pub fn submit_misbehaviour(
+        &mut self,
+        msg: MsgSubmitMisbehaviour,
+    ) -> anyhow::Result<MockResponse> {
+        let client_message = WasmClientMessage::decode(
+            msg.misbehaviour
+                .context("missing misbehaviour")?
+                .value
+                .as_ref(),
+        )?;
+
+        let misbehaviour: Misbehaviour<C> =
+            MisbehaviourProto::decode(client_message.data.as_slice())?.try_into()?;
+
+        self.apply_misbehaviour(&msg.client_id, misbehaviour)
+    }
+
+    fn trusted_consensus_state(
+        &self,
+        client_id: &str,
+        trusted_height: u64,
+    ) -> anyhow::Result<ConsensusState> {
+        let client = self.client(client_id)?;
+
+        anyhow::ensure!(!client.is_frozen(), "client {client_id} is frozen");
+
+        let trusted_consensus_state = client
+            .consensus_states
+            .get(&trusted_height)
+            .with_context(|| format!("no consensus state at trusted height {trusted_height}"))?;
+
+        anyhow::ensure!(
+            !self.is_expired(trusted_consensus_state),
+            "consensus state of {client_id} at {trusted_height} is outside of the trusting period",
+        );
+
+        Ok(trusted_consensus_state.clone())
+    }
+
+    pub fn apply_header(
+        &mut self,
+        client_id: &str,
+        header: Header<C>,
+    ) -> anyhow::Result<MockResponse> {
+        let Header {
+            trusted_sync_committee,
+            consensus_update,
+            account_update,
+        } = header;
+
+        let trusted_height = trusted_sync_committee.trusted_height.revision_height;
+
+        let trusted_consensus_state = self.trusted_consensus_state(client_id, trusted_height)?;
+
+        let client = self
+            .clients
+            .get_mut(client_id)
+            .with_context(|| format!("client {client_id} not found"))?;
+
+        let height = consensus_update.finalized_header.beacon.slot;
+
+        let slots_per_period = client.client_state.slots_per_epoch
+            * client.client_state.epochs_per_sync_committee_period;
+
+        let update_next_sync_committee = consensus_update
+            .next_sync_committee
+            .map(|sync_committee| sync_committee.aggregate_pubkey);
+
+        // rotate the committees when the update crosses into the next period
+        let (current_sync_committee, next_sync_committee) =
+            if height / slots_per_period > trusted_height / slots_per_period {
+                (
+                    trusted_consensus_state
+                        .next_sync_committee
+                        .context("next sync committee is unknown")?,
+                    update_next_sync_committee,
+                )
+            } else {
+                (
+                    trusted_consensus_state.current_sync_committee,
+                    update_next_sync_committee.or(trusted_consensus_state.next_sync_committee),
+                )
+            };
+
+        let consensus_state = ConsensusState {
+            slot: height,
+            state_root: consensus_update.finalized_header.execution.state_root,
+            storage_root: account_update.account_proof.storage_root,
+            // Normalize to nanos in order to be compliant with cosmos
+            timestamp: consensus_update.finalized_header.execution.timestamp * 1_000_000_000,
+            current_sync_committee,
+            next_sync_committee,
+        };
+
+        // a different consensus state for an already known height is a fork
+        if let Some(existing) = client.consensus_states.get(&height) {
+            if *existing != consensus_state {
+                client.client_state.frozen_height = Height {
+                    revision_number: 0,
+                    revision_height: height,
+                };
+
+                return Ok(MockResponse::ClientFrozen {
+                    client_id: client_id.to_string(),
+                    height,
+                });
+            }
+        }
+
+        anyhow::ensure!(
+            height > client.latest_height(),
+            "update height {} must be greater than the latest height {}",
+            height,
+            client.latest_height(),
+        );
+
+        client.client_state.latest_slot = height;
+        client.consensus_states.insert(height, consensus_state);
+        client.optimistic_heads.finalize(height);
+
+        Ok(MockResponse::ClientUpdated {
+            client_id: client_id.to_string(),
+            height,
+        })
+    }
+
+    // tracked next to the finalized consensus states, never turned into one
+    pub fn apply_optimistic_header(
+        &mut self,
+        client_id: &str,
+        header: OptimisticHeader<C>,
+    ) -> anyhow::Result<MockResponse> {
+        let trusted_height = header.trusted_sync_committee.trusted_height.revision_height;
+
+        self.trusted_consensus_state(client_id, trusted_height)?;
+
+        let client = self
+            .clients
+            .get_mut(client_id)
+            .with_context(|| format!("client {client_id} not found"))?;
+
+        let attested_header = header.optimistic_update.attested_header;
+        let height = attested_header.beacon.slot;
+        let beacon = attested_header.beacon.clone();
+
+        let replaced = client
+            .optimistic_heads
+            .insert(client.client_state.latest_slot, attested_header)?;
+
+        Ok(MockResponse::OptimisticUpdated {
+            client_id: client_id.to_string(),
+            height,
+            reorged: replaced.is_some_and(|replaced| replaced.beacon != beacon),
+        })
+    }
+
+    pub fn apply_misbehaviour(
+        &mut self,
+        client_id: &str,
+        misbehaviour: Misbehaviour<C>,
+    ) -> anyhow::Result<MockResponse> {
+        let client = self
+            .clients
+            .get_mut(client_id)
+            .with_context(|| format!("client {client_id} not found"))?;
+
+        anyhow::ensure!(!client.is_frozen(), "client {client_id} is already frozen");
+
+        let trusted_height = misbehaviour
+            .trusted_sync_committee
+            .trusted_height
+            .revision_height;
+
+        anyhow::ensure!(
+            client.consensus_states.contains_key(&trusted_height),
+            "no consensus state at trusted height {trusted_height}",
+        );
+
+        let height_1 = misbehaviour.update_1.finalized_header.beacon.slot;
+        let height_2 = misbehaviour.update_2.finalized_header.beacon.slot;
+
+        anyhow::ensure!(
+            height_1 == height_2,
+            "misbehaviour updates must finalize the same slot, got {height_1} and {height_2}",
+        );
+        anyhow::ensure!(
+            misbehaviour.update_1.finalized_header != misbehaviour.update_2.finalized_header,
+            "misbehaviour updates finalize the same header",
+        );
+
+        client.client_state.frozen_height = Height {
+            revision_number: 0,
+            revision_height: height_1,
+        };
+
+        Ok(MockResponse::ClientFrozen {
+            client_id: client_id.to_string(),
+            height: height_1,
+        })
+    }
+}
+
+// a single client of the mock, driven as a daemon counterparty
+pub struct MockClientHandle<C> {
+    pub counterparty: MockCounterparty<C>,
+    pub client_id: String,
+    pub signer: String,
+}
+
+impl<C> Counterparty<C> for MockClientHandle<C>
+where
+    C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES + Send + Sync,
+{
+    async fn latest_height(&self) -> anyhow::Result<u64> {
+        Ok(self.counterparty.client(&self.client_id)?.latest_height())
+    }
+
+    async fn latest_timestamp(&self) -> anyhow::Result<u64> {
+        Ok(self
+            .counterparty
+            .client(&self.client_id)?
+            .latest_consensus_state()
+            .context("latest consensus state")?
+            .timestamp)
+    }
+
+    async fn submit(&mut self, header: Header<C>) -> anyhow::Result<()> {
+        self.counterparty.handle(msg_update_client(
+            self.client_id.clone(),
+            header,
+            self.signer.clone(),
+        ))?;
+
+        Ok(())
+    }
+
+    async fn submit_optimistic(&mut self, header: OptimisticHeader<C>) -> anyhow::Result<()> {
+        self.counterparty
+            .apply_optimistic_header(&self.client_id, header)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use alloy_signer_local::coins_bip39::English;
+use alloy_signer_local::MnemonicBuilder;
+use bech32::{ToBase32, Variant};
+use bon::Builder;
+use k256::ecdsa::signature::Signer;
+use k256::ecdsa::{Signature, SigningKey};
+use prost::Message;
+use protos::cosmos::base::v1beta1::Coin;
+use protos::cosmos::crypto::secp256k1::PubKey;
+use protos::cosmos::tx::signing::v1beta1::SignMode;
+use protos::cosmos::tx::v1beta1::{
+    mode_info, AuthInfo, Fee, ModeInfo, SignDoc, SignerInfo, TxBody, TxRaw,
+};
+use protos::google::protobuf::Any;
+use protos::ibc::core::client::v1::{
+    Height as HeightProto, MsgCreateClient, MsgSubmitMisbehaviour, MsgUpdateClient,
+};
+use protos::ibc::lightclients::wasm::v1::{
+    ClientMessage as WasmClientMessage, ClientState as WasmClientState,
+    ConsensusState as WasmConsensusState,
+};
+use protos::union::ibc::lightclients::ethereum::v1::{
+    ClientState as ClientStateProto, ConsensusState as ConsensusStateProto, Header as HeaderProto,
+    Misbehaviour as MisbehaviourProto,
+};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+use unionlabs::ethereum::config::{
+    BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES, SYNC_COMMITTEE_SIZE,
+};
+use unionlabs::ibc::lightclients::ethereum::client_state::ClientState;
+use unionlabs::ibc::lightclients::ethereum::consensus_state::ConsensusState;
+use unionlabs::ibc::lightclients::ethereum::header::Header;
+use unionlabs::ibc::lightclients::ethereum::misbehaviour::Misbehaviour;
+
+pub mod mock;
+pub mod wasm;
+
+pub const COSMOS_HD_PATH: &str = "m/44'/118'/0'/0/0";
+
+pub const MSG_CREATE_CLIENT_TYPE_URL: &str = "/ibc.core.client.v1.MsgCreateClient";
+pub const MSG_UPDATE_CLIENT_TYPE_URL: &str = "/ibc.core.client.v1.MsgUpdateClient";
+pub const MSG_SUBMIT_MISBEHAVIOUR_TYPE_URL: &str = "/ibc.core.client.v1.MsgSubmitMisbehaviour";
+
+pub const WASM_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.ClientState";
+pub const WASM_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.ConsensusState";
+pub const WASM_CLIENT_MESSAGE_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.ClientMessage";
+
+pub const SECP256K1_PUBKEY_TYPE_URL: &str = "/cosmos.crypto.secp256k1.PubKey";
+
+pub fn to_any(type_url: &str, message: &impl Message) -> Any {
+    Any {
+        type_url: type_url.to_string(),
+        value: message.encode_to_vec().into(),
+    }
+}
+
+pub fn msg_create_client(
+    client_state: ClientState,
+    consensus_state: ConsensusState,
+    checksum: Vec<u8>,
+    signer: String,
+) -> Any {
+    let latest_height = HeightProto {
+        revision_number: 0,
+        revision_height: client_state.latest_slot,
+    };
+
+    let client_state = WasmClientState {
+        data: ClientStateProto::from(client_state).encode_to_vec(),
+        checksum,
+        latest_height: Some(latest_height),
+    };
+
+    let consensus_state = WasmConsensusState {
+        data: ConsensusStateProto::from(consensus_state).encode_to_vec(),
+    };
+
+    to_any(
+        MSG_CREATE_CLIENT_TYPE_URL,
+        &MsgCreateClient {
+            client_state: Some(to_any(WASM_CLIENT_STATE_TYPE_URL, &client_state)),
+            consensus_state: Some(to_any(WASM_CONSENSUS_STATE_TYPE_URL, &consensus_state)),
+            signer,
+        },
+    )
+}
+
+pub fn msg_update_client<C>(client_id: String, header: Header<C>, signer: String) -> Any
+where
+    C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES,
+{
+    let client_message = WasmClientMessage {
+        data: HeaderProto::from(header).encode_to_vec(),
+    };
+
+    to_any(
+        MSG_UPDATE_CLIENT_TYPE_URL,
+        &MsgUpdateClient {
+            client_id,
+            client_message: Some(to_any(WASM_CLIENT_MESSAGE_TYPE_URL, &client_message)),
+            signer,
+        },
+    )
+}
+
+// MsgSubmitMisbehaviour is deprecated in ibc-go in favour of MsgUpdateClient, but wasm
+// clients still accept it
+#[allow(deprecated)]
+pub fn msg_submit_misbehaviour<C>(
+    client_id: String,
+    misbehaviour: Misbehaviour<C>,
+    signer: String,
+) -> Any
+where
+    C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES,
+{
+    let client_message = WasmClientMessage {
+        data: MisbehaviourProto::from(misbehaviour).encode_to_vec(),
+    };
+
+    to_any(
+        MSG_SUBMIT_MISBEHAVIOUR_TYPE_URL,
+        &MsgSubmitMisbehaviour {
+            client_id,
+            misbehaviour: Some(to_any(WASM_CLIENT_MESSAGE_TYPE_URL, &client_message)),
+            signer,
+        },
+    )
+}
+
+#[derive(Builder, Debug, Clone)]
+pub struct TxContext {
+    #[builder(into)]
+    pub chain_id: String,
+    pub account_number: u64,
+    pub sequence: u64,
+    #[builder(default = 200_000)]
+    pub gas_limit: u64,
+    #[builder(default)]
+    pub fee: Vec<Coin>,
+    #[builder(default, into)]
+    pub memo: String,
+    #[builder(default)]
+    pub timeout_height: u64,
+}
+
+pub struct CosmosSigner {
+    pub signing_key: SigningKey,
+    pub prefix: String,
+}
+
+impl CosmosSigner {
+    pub fn from_mnemonic(phrase: &str, prefix: &str) -> anyhow::Result<Self> {
+        let wallet = MnemonicBuilder::<English>::default()
+            .phrase(phrase)
+            .derivation_path(COSMOS_HD_PATH)?
+            .build()?;
+
+        Ok(Self {
+            signing_key: wallet.into_credential(),
+            prefix: prefix.to_string(),
+        })
+    }
+
+    pub fn public_key(&self) -> Vec<u8> {
+        self.signing_key
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec()
+    }
+
+    pub fn address(&self) -> anyhow::Result<String> {
+        let hash = Ripemd160::digest(Sha256::digest(self.public_key()));
+        Ok(bech32::encode(
+            &self.prefix,
+            hash.to_base32(),
+            Variant::Bech32,
+        )?)
+    }
+
+    pub fn sign_tx(&self, messages: Vec<Any>, context: &TxContext) -> anyhow::Result<Vec<u8>> {
+        anyhow::ensure!(!messages.is_empty(), "tx must contain at least one message");
+
+        let body = TxBody {
+            messages,
+            memo: context.memo.clone(),
+            timeout_height: context.timeout_height,
+            ..Default::default()
+        };
+
+        let signer_info = SignerInfo {
+            public_key: Some(to_any(
+                SECP256K1_PUBKEY_TYPE_URL,
+                &PubKey {
+                    key: self.public_key(),
+                },
+            )),
+            mode_info: Some(ModeInfo {
+                sum: Some(mode_info::Sum::Single(mode_info::Single {
+                    mode: SignMode::Direct.into(),
+                })),
+            }),
+            sequence: context.sequence,
+        };
+
+        let auth_info = AuthInfo {
+            signer_infos: vec![signer_info],
+            fee: Some(Fee {
+                amount: context.fee.clone(),
+                gas_limit: context.gas_limit,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let body_bytes = body.encode_to_vec();
+        let auth_info_bytes = auth_info.encode_to_vec();
+
+        let sign_doc = SignDoc {
+            body_bytes: body_bytes.clone(),
+            auth_info_bytes: auth_info_bytes.clone(),
+            chain_id: context.chain_id.clone(),
+            account_number: context.account_number,
+        };
+
+        // secp256k1 over sha256(sign_doc), low-s normalized as the sdk requires
+        let signature: Signature = self.signing_key.sign(&sign_doc.encode_to_vec());
+
+        let tx_raw = TxRaw {
+            body_bytes,
+            auth_info_bytes,
+            signatures: vec![signature.to_bytes().to_vec()],
+        };
+
+        Ok(tx_raw.encode_to_vec())
+    }
+}
@@ -0,0 +1,297 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::Context;
+use blst::min_pk::{AggregatePublicKey, PublicKey, Signature};
+use blst::BLST_ERROR;
+use cosmwasm_std::{
+    to_json_binary, Binary, ContractResult, CustomQuery, Env, Response, SystemResult, Timestamp,
+};
+use cosmwasm_vm::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_vm::{
+    call_instantiate_raw, call_query_raw, call_sudo_raw, Backend, Instance, InstanceOptions, Size,
+};
+use ics008_wasm_client::MerklePath;
+use prost::Message;
+use protos::union::ibc::lightclients::ethereum::v1::{
+    ClientState as ClientStateProto, ConsensusState as ConsensusStateProto, Header as HeaderProto,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use unionlabs::ethereum::config::{
+    BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES, SYNC_COMMITTEE_SIZE,
+};
+use unionlabs::ibc::lightclients::ethereum::client_state::ClientState;
+use unionlabs::ibc::lightclients::ethereum::consensus_state::ConsensusState;
+use unionlabs::ibc::lightclients::ethereum::header::Header;
+
+pub const ETHEREUM_LIGHT_CLIENT_WASM_ENV: &str = "ETHEREUM_LIGHT_CLIENT_WASM";
+pub const ETHEREUM_LIGHT_CLIENT_WASM_PATH: &str = "light-clients/ethereum-light-client.wasm";
+
+pub const BLS_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+const GAS_LIMIT: u64 = 1 << 60;
+const MEMORY_LIMIT_MIB: usize = 64;
+
+// the union light clients offload bls to the host chain through custom queries
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum UnionCustomQuery {
+    AggregateVerify {
+        public_keys: Vec<Binary>,
+        message: Binary,
+        signature: Binary,
+    },
+    Aggregate {
+        public_keys: Vec<Binary>,
+    },
+}
+
+impl CustomQuery for UnionCustomQuery {}
+
+fn bls_error(err: BLST_ERROR) -> anyhow::Error {
+    anyhow::anyhow!("bls error: {:?}", err)
+}
+
+fn public_keys(public_keys: &[Binary]) -> anyhow::Result<Vec<PublicKey>> {
+    public_keys
+        .iter()
+        .map(|public_key| PublicKey::from_bytes(public_key.as_slice()).map_err(bls_error))
+        .collect()
+}
+
+fn handle_custom_query(query: &UnionCustomQuery) -> anyhow::Result<Binary> {
+    match query {
+        UnionCustomQuery::AggregateVerify {
+            public_keys: keys,
+            message,
+            signature,
+        } => {
+            let keys = public_keys(keys)?;
+            let signature = Signature::from_bytes(signature.as_slice()).map_err(bls_error)?;
+
+            let valid = signature.fast_aggregate_verify(
+                true,
+                message.as_slice(),
+                BLS_DST,
+                &keys.iter().collect::<Vec<_>>(),
+            ) == BLST_ERROR::BLST_SUCCESS;
+
+            Ok(to_json_binary(&valid)?)
+        }
+        UnionCustomQuery::Aggregate { public_keys: keys } => {
+            let keys = public_keys(keys)?;
+
+            let aggregate = AggregatePublicKey::aggregate(&keys.iter().collect::<Vec<_>>(), true)
+                .map_err(bls_error)?;
+
+            Ok(to_json_binary(&Binary::from(
+                aggregate.to_public_key().compress().to_vec(),
+            ))?)
+        }
+    }
+}
+
+type WasmInstance = Instance<MockApi, MockStorage, MockQuerier<UnionCustomQuery>>;
+
+// an 08-wasm host in a box: the light client contract with its own storage
+pub struct WasmLightClient {
+    pub checksum: Vec<u8>,
+    pub instance: WasmInstance,
+    pub env: Env,
+}
+
+impl WasmLightClient {
+    // the blob is not vendored in git; `just light-client` builds it
+    pub fn default_path() -> anyhow::Result<PathBuf> {
+        let path = PathBuf::from(
+            std::env::var(ETHEREUM_LIGHT_CLIENT_WASM_ENV)
+                .unwrap_or_else(|_| ETHEREUM_LIGHT_CLIENT_WASM_PATH.to_string()),
+        );
+
+        anyhow::ensure!(
+            path.is_file(),
+            "no ethereum light client wasm at {}, build it with `just light-client` or set {}",
+            path.display(),
+            ETHEREUM_LIGHT_CLIENT_WASM_ENV,
+        );
+
+        Ok(path)
+    }
+
+    pub fn load_default() -> anyhow::Result<Self> {
+        Self::load(Self::default_path()?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let code = std::fs::read(path)
+            .with_context(|| format!("failed to read light client wasm at {}", path.display()))?;
+
+        Self::from_code(&code)
+    }
+
+    pub fn from_code(code: &[u8]) -> anyhow::Result<Self> {
+        let backend = Backend {
+            api: MockApi::default(),
+            storage: MockStorage::default(),
+            querier: MockQuerier::<UnionCustomQuery>::new(&[]).with_custom_handler(|query| {
+                SystemResult::Ok(match handle_custom_query(query) {
+                    Ok(response) => ContractResult::Ok(response),
+                    Err(err) => ContractResult::Err(err.to_string()),
+                })
+            }),
+        };
+
+        let instance = Instance::from_code(
+            code,
+            backend,
+            InstanceOptions {
+                gas_limit: GAS_LIMIT,
+                print_debug: false,
+            },
+            Some(Size::mebi(MEMORY_LIMIT_MIB)),
+        )?;
+
+        Ok(Self {
+            checksum: Sha256::digest(code).to_vec(),
+            instance,
+            env: mock_env(),
+        })
+    }
+
+    fn env_bytes(&mut self) -> anyhow::Result<Vec<u8>> {
+        // the client checks headers against wall clock time
+        self.env.block.time = Timestamp::from_nanos(
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .as_nanos()
+                .try_into()?,
+        );
+        self.env.block.height += 1;
+
+        Ok(serde_json::to_vec(&self.env)?)
+    }
+
+    fn sudo(&mut self, msg: serde_json::Value) -> anyhow::Result<Response> {
+        let env = self.env_bytes()?;
+        let result = call_sudo_raw(&mut self.instance, &env, &serde_json::to_vec(&msg)?)?;
+
+        serde_json::from_slice::<ContractResult<Response>>(&result)?
+            .into_result()
+            .map_err(anyhow::Error::msg)
+    }
+
+    fn query(&mut self, msg: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+        let env = self.env_bytes()?;
+        let result = call_query_raw(&mut self.instance, &env, &serde_json::to_vec(&msg)?)?;
+
+        let binary = serde_json::from_slice::<ContractResult<Binary>>(&result)?
+            .into_result()
+            .map_err(anyhow::Error::msg)?;
+
+        Ok(serde_json::from_slice(binary.as_slice())?)
+    }
+
+    pub fn instantiate(
+        &mut self,
+        client_state: ClientState,
+        consensus_state: ConsensusState,
+    ) -> anyhow::Result<()> {
+        let msg = json!({
+            "client_state": Binary::from(ClientStateProto::from(client_state).encode_to_vec()),
+            "consensus_state": Binary::from(ConsensusStateProto::from(consensus_state).encode_to_vec()),
+            "checksum": Binary::from(self.checksum.clone()),
+        });
+
+        let env = self.env_bytes()?;
+        let info = serde_json::to_vec(&mock_info("ibc", &[]))?;
+        let result =
+            call_instantiate_raw(&mut self.instance, &env, &info, &serde_json::to_vec(&msg)?)?;
+
+        serde_json::from_slice::<ContractResult<Response>>(&result)?
+            .into_result()
+            .map_err(anyhow::Error::msg)?;
+
+        Ok(())
+    }
+
+    pub fn status(&mut self) -> anyhow::Result<String> {
+        let response = self.query(json!({ "status": {} }))?;
+
+        Ok(response["status"]
+            .as_str()
+            .context("status response")?
+            .to_string())
+    }
+
+    // mirrors 08-wasm: verify_client_message, check_for_misbehaviour, then update_state
+    pub fn update<C>(&mut self, header: Header<C>) -> anyhow::Result<()>
+    where
+        C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES,
+    {
+        let client_message = Binary::from(HeaderProto::from(header).encode_to_vec());
+
+        self.query(json!({
+            "verify_client_message": { "client_message": client_message },
+        }))?;
+
+        let misbehaviour = self.query(json!({
+            "check_for_misbehaviour": { "client_message": client_message },
+        }))?;
+
+        anyhow::ensure!(
+            !misbehaviour["found_misbehaviour"]
+                .as_bool()
+                .unwrap_or_default(),
+            "light client found misbehaviour",
+        );
+
+        self.sudo(json!({
+            "update_state": { "client_message": client_message },
+        }))?;
+
+        Ok(())
+    }
+
+    pub fn verify_membership(
+        &mut self,
+        height: u64,
+        proof: Vec<u8>,
+        path: MerklePath,
+        value: Vec<u8>,
+    ) -> anyhow::Result<()> {
+        self.sudo(json!({
+            "verify_membership": {
+                "height": { "revision_number": 0, "revision_height": height },
+                "delay_time_period": 0,
+                "delay_block_period": 0,
+                "proof": Binary::from(proof),
+                "path": { "key_path": path.key_path },
+                "value": Binary::from(value),
+            },
+        }))?;
+
+        Ok(())
+    }
+
+    pub fn verify_non_membership(
+        &mut self,
+        height: u64,
+        proof: Vec<u8>,
+        path: MerklePath,
+    ) -> anyhow::Result<()> {
+        self.sudo(json!({
+            "verify_non_membership": {
+                "height": { "revision_number": 0, "revision_height": height },
+                "delay_time_period": 0,
+                "delay_block_period": 0,
+                "proof": Binary::from(proof),
+                "path": { "key_path": path.key_path },
+            },
+        }))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+use core::net::SocketAddr;
+use core::str::FromStr;
+use core::task::{Context, Poll};
+use std::time::SystemTime;
+
+use alloy::providers::{IpcConnect, Provider, ProviderBuilder, WsConnect};
+use alloy::rpc::client::{ClientBuilder, RpcClient};
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::{
+    Authorization, BoxTransport, TransportError, TransportErrorKind, TransportFut,
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use bon::Builder;
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::Url;
+use sha2::Sha256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    Http,
+    Ws,
+    Ipc,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum Auth {
+    Bearer(String),
+    // 32 byte engine api secret, an HS256 token is signed whenever a header map is built: per
+    // request for beacon api and json-rpc over http calls, but once per connection for ws
+    Jwt([u8; 32]),
+}
+
+impl core::fmt::Debug for Auth {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Auth::Bearer(_) => f.write_str("Bearer(..)"),
+            Auth::Jwt(_) => f.write_str("Jwt(..)"),
+        }
+    }
+}
+
+impl Auth {
+    // hex encoded, with or without 0x, as in a jwt.hex file
+    pub fn jwt_from_hex(secret: &str) -> anyhow::Result<Self> {
+        let secret = hex::decode(secret.trim().trim_start_matches("0x"))?;
+
+        Ok(Auth::Jwt(secret.try_into().map_err(|x: Vec<u8>| {
+            anyhow::anyhow!("jwt secret should be 32 bytes but got {}", x.len())
+        })?))
+    }
+
+    pub fn token(&self) -> anyhow::Result<String> {
+        self.token_at(SystemTime::now())
+    }
+
+    // the engine api only accepts an iat within 60s of its own clock
+    pub fn token_at(&self, now: SystemTime) -> anyhow::Result<String> {
+        match self {
+            Auth::Bearer(token) => Ok(token.clone()),
+            Auth::Jwt(secret) => {
+                let iat = now.duration_since(std::time::UNIX_EPOCH)?.as_secs();
+
+                let message = format!(
+                    "{}.{}",
+                    URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+                    URL_SAFE_NO_PAD.encode(format!(r#"{{"iat":{}}}"#, iat)),
+                );
+
+                let mut mac = Hmac::<Sha256>::new_from_slice(secret)?;
+                mac.update(message.as_bytes());
+
+                Ok(format!(
+                    "{}.{}",
+                    message,
+                    URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+                ))
+            }
+        }
+    }
+}
+
+// where a node is reached: http(s), ws(s) or an ipc path, with the auth and headers it expects
+#[derive(Debug, Clone, PartialEq, Eq, Builder)]
+pub struct Endpoint {
+    pub url: Url,
+    pub auth: Option<Auth>,
+    #[builder(default)]
+    pub headers: Vec<(String, String)>,
+}
+
+impl From<SocketAddr> for Endpoint {
+    fn from(socket: SocketAddr) -> Self {
+        Self {
+            url: format!("http://{}", socket)
+                .parse()
+                .expect("socket is a valid url"),
+            auth: None,
+            headers: vec![],
+        }
+    }
+}
+
+// a bare `host:port` is http and a bare path is ipc, anything else has to be a url
+impl FromStr for Endpoint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(socket) = s.parse::<SocketAddr>() {
+            return Ok(socket.into());
+        }
+
+        let url = if s.starts_with('/') {
+            Url::from_file_path(s).map_err(|()| anyhow::anyhow!("invalid ipc path {}", s))?
+        } else {
+            s.parse()?
+        };
+
+        Ok(Self {
+            url,
+            auth: None,
+            headers: vec![],
+        })
+    }
+}
+
+// the url without a trailing slash, so paths can be appended to it
+impl core::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.url.as_str().trim_end_matches('/'))
+    }
+}
+
+impl Endpoint {
+    // reads `<prefix>_ENDPOINT` and optionally `<prefix>_JWT_SECRET`, `<prefix>_BEARER_TOKEN`
+    // and `<prefix>_HEADERS` as comma separated `name=value` pairs
+    pub fn from_env(prefix: &str) -> anyhow::Result<Option<Self>> {
+        let var = |name: &str| std::env::var(format!("{}_{}", prefix, name)).ok();
+
+        let Some(endpoint) = var("ENDPOINT") else {
+            return Ok(None);
+        };
+
+        let mut endpoint = endpoint.parse::<Self>()?;
+
+        endpoint.auth = match (var("JWT_SECRET"), var("BEARER_TOKEN")) {
+            (Some(_), Some(_)) => {
+                anyhow::bail!("{0}_JWT_SECRET and {0}_BEARER_TOKEN are exclusive", prefix)
+            }
+            (Some(secret), None) => Some(Auth::jwt_from_hex(&secret)?),
+            (None, Some(token)) => Some(Auth::Bearer(token)),
+            (None, None) => None,
+        };
+
+        endpoint.headers = var("HEADERS")
+            .iter()
+            .flat_map(|headers| headers.split(','))
+            .filter(|header| !header.is_empty())
+            .map(|header| {
+                header
+                    .split_once('=')
+                    .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                    .ok_or_else(|| anyhow::anyhow!("header {} is not name=value", header))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Some(endpoint))
+    }
+
+    pub fn scheme(&self) -> anyhow::Result<Scheme> {
+        match self.url.scheme() {
+            "http" | "https" => Ok(Scheme::Http),
+            "ws" | "wss" => Ok(Scheme::Ws),
+            "file" | "ipc" => Ok(Scheme::Ipc),
+            scheme => anyhow::bail!("unsupported scheme {}", scheme),
+        }
+    }
+
+    // rest path under the endpoint, keeping any path prefix of the url
+    pub fn join(&self, path: &str) -> anyhow::Result<Url> {
+        Ok(format!("{}{}", self, path).parse()?)
+    }
+
+    pub fn header_map(&self) -> anyhow::Result<HeaderMap> {
+        self.header_map_at(SystemTime::now())
+    }
+
+    pub fn header_map_at(&self, now: SystemTime) -> anyhow::Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+
+        for (name, value) in &self.headers {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(value)?,
+            );
+        }
+
+        if let Some(auth) = &self.auth {
+            let mut value = HeaderValue::from_str(&format!("Bearer {}", auth.token_at(now)?))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+
+        Ok(headers)
+    }
+
+    // ws only carries the auth, headers are an http thing
+    pub async fn rpc_client(&self) -> anyhow::Result<RpcClient<BoxTransport>> {
+        Ok(match self.scheme()? {
+            Scheme::Http => ClientBuilder::default()
+                .transport(
+                    HeaderHttp::builder().endpoint(self.clone()).build(),
+                    alloy::transports::utils::guess_local_url(&self.url),
+                )
+                .boxed(),
+            Scheme::Ws => {
+                let mut connect = WsConnect::new(self.url.to_string());
+
+                if let Some(auth) = &self.auth {
+                    connect = connect.with_auth(Authorization::bearer(auth.token()?));
+                }
+
+                ClientBuilder::default().ws(connect).await?.boxed()
+            }
+            Scheme::Ipc => ClientBuilder::default()
+                .ipc(IpcConnect::new(std::path::PathBuf::from(self.url.path())))
+                .await?
+                .boxed(),
+        })
+    }
+
+    pub async fn provider(&self) -> anyhow::Result<impl Provider> {
+        Ok(ProviderBuilder::new()
+            .with_recommended_fillers()
+            .on_client(self.rpc_client().await?))
+    }
+}
+
+// json-rpc over http with the headers built per request rather than once per client, so a
+// long lived client keeps sending a jwt with a fresh iat
+#[derive(Debug, Clone, Builder)]
+pub struct HeaderHttp {
+    pub endpoint: Endpoint,
+    // what tokens are signed at, only replaced to test expiry
+    #[builder(default = SystemTime::now as fn() -> SystemTime)]
+    pub clock: fn() -> SystemTime,
+    #[builder(skip)]
+    client: reqwest::Client,
+}
+
+impl HeaderHttp {
+    async fn request(self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let headers = self
+            .endpoint
+            .header_map_at((self.clock)())
+            .map_err(|err| TransportErrorKind::custom_str(&err.to_string()))?;
+
+        let response = self
+            .client
+            .post(self.endpoint.url)
+            .headers(headers)
+            .json(&request)
+            .send()
+            .await
+            .map_err(TransportErrorKind::custom)?;
+
+        let status = response.status();
+        let body = response.bytes().await.map_err(TransportErrorKind::custom)?;
+
+        if !status.is_success() {
+            return Err(TransportErrorKind::http_error(
+                status.as_u16(),
+                String::from_utf8_lossy(&body).into_owned(),
+            ));
+        }
+
+        serde_json::from_slice(&body)
+            .map_err(|err| TransportError::deser_err(err, String::from_utf8_lossy(&body)))
+    }
+}
+
+impl tower::Service<RequestPacket> for HeaderHttp {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        Box::pin(self.clone().request(request))
+    }
+}
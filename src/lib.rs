@@ -1,4 +1,8 @@
 #[cfg(test)]
 pub mod tests;
 
+pub mod cosmos;
+pub mod endpoint;
+pub mod light_client;
+pub mod merkle;
 pub mod relayer;
@@ -0,0 +1,791 @@
+use alloy::primitives::U256;
+use anyhow::Context;
+use blst::min_pk::{PublicKey, Signature};
+use blst::BLST_ERROR;
+use bon::Builder;
+use serde_json::Value;
+use unionlabs::ethereum::beacon::light_client_bootstrap::UnboundedLightClientBootstrap;
+use unionlabs::ibc::lightclients::ethereum::light_client_header::UnboundedLightClientHeader;
+use unionlabs::ibc::lightclients::ethereum::light_client_update::UnboundedLightClientUpdate;
+use unionlabs::ibc::lightclients::ethereum::sync_committee::UnboundedSyncCommittee;
+
+use crate::cosmos::wasm::BLS_DST;
+use crate::merkle::{self, Root};
+use crate::relayer::ssz::Fork;
+
+pub const DOMAIN_SYNC_COMMITTEE: [u8; 4] = [7, 0, 0, 0];
+pub const GENESIS_SLOT: u64 = 0;
+
+// subtree index of the execution payload in the block body
+const EXECUTION_PAYLOAD_SUBTREE_INDEX: u64 = 9;
+
+pub trait BlsVerifier {
+    fn fast_aggregate_verify(
+        &self,
+        public_keys: &[&[u8]],
+        message: &[u8],
+        signature: &[u8],
+    ) -> anyhow::Result<bool>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blst;
+
+impl BlsVerifier for Blst {
+    fn fast_aggregate_verify(
+        &self,
+        public_keys: &[&[u8]],
+        message: &[u8],
+        signature: &[u8],
+    ) -> anyhow::Result<bool> {
+        let bls_error = |err: BLST_ERROR| anyhow::anyhow!("bls error: {:?}", err);
+
+        let public_keys = public_keys
+            .iter()
+            .map(|public_key| PublicKey::from_bytes(public_key).map_err(bls_error))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let signature = Signature::from_bytes(signature).map_err(bls_error)?;
+
+        Ok(signature.fast_aggregate_verify(
+            true,
+            message,
+            BLS_DST,
+            &public_keys.iter().collect::<Vec<_>>(),
+        ) == BLST_ERROR::BLST_SUCCESS)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkSchedule {
+    pub epoch: u64,
+    pub version: [u8; 4],
+    // None for the genesis fork
+    pub fork: Option<Fork>,
+}
+
+#[derive(Debug, Clone, Builder)]
+pub struct LightClientConfig {
+    pub genesis_validators_root: Root,
+    pub genesis_time: u64,
+    pub seconds_per_slot: u64,
+    pub slots_per_epoch: u64,
+    pub epochs_per_sync_committee_period: u64,
+    // ascending by epoch, starting with the genesis fork
+    pub forks: Vec<ForkSchedule>,
+    #[builder(default = 1)]
+    pub min_sync_committee_participants: u64,
+}
+
+// beacon api json has uint64 as decimal strings
+fn u64_field(value: &Value, key: &str) -> anyhow::Result<u64> {
+    match &value[key] {
+        Value::String(value) => Ok(value.parse()?),
+        value => value
+            .as_u64()
+            .with_context(|| format!("{} is not a uint64", key)),
+    }
+}
+
+fn bytes_field(value: &Value, key: &str) -> anyhow::Result<Vec<u8>> {
+    Ok(hex::decode(
+        value[key]
+            .as_str()
+            .with_context(|| format!("{} is not a hex string", key))?
+            .trim_start_matches("0x"),
+    )?)
+}
+
+fn fork_version(spec: &Value, key: &str) -> anyhow::Result<[u8; 4]> {
+    <[u8; 4]>::try_from(bytes_field(spec, key)?)
+        .map_err(|x| anyhow::anyhow!("invalid fork version {:?}", x))
+}
+
+impl LightClientConfig {
+    // from the `data` of /eth/v1/config/spec and /eth/v1/beacon/genesis
+    pub fn from_spec(spec: &Value, genesis: &Value) -> anyhow::Result<Self> {
+        let mut forks = vec![ForkSchedule {
+            epoch: 0,
+            version: fork_version(spec, "GENESIS_FORK_VERSION")?,
+            fork: None,
+        }];
+
+        for fork in Fork::ALL {
+            // forks unknown to the beacon node are not scheduled
+            if spec[fork.version_key()].is_null() {
+                continue;
+            }
+
+            forks.push(ForkSchedule {
+                epoch: u64_field(spec, &format!("{}_FORK_EPOCH", fork.name().to_uppercase()))?,
+                version: fork_version(spec, &fork.version_key())?,
+                fork: Some(fork),
+            });
+        }
+
+        Ok(Self {
+            genesis_validators_root: merkle::bytes32(bytes_field(
+                genesis,
+                "genesis_validators_root",
+            )?)?,
+            genesis_time: u64_field(genesis, "genesis_time")?,
+            seconds_per_slot: u64_field(spec, "SECONDS_PER_SLOT")?,
+            slots_per_epoch: u64_field(spec, "SLOTS_PER_EPOCH")?,
+            epochs_per_sync_committee_period: u64_field(spec, "EPOCHS_PER_SYNC_COMMITTEE_PERIOD")?,
+            forks,
+            min_sync_committee_participants: u64_field(spec, "MIN_SYNC_COMMITTEE_PARTICIPANTS")
+                .unwrap_or(1),
+        })
+    }
+
+    pub fn slots_per_period(&self) -> u64 {
+        self.slots_per_epoch * self.epochs_per_sync_committee_period
+    }
+
+    pub fn period_at_slot(&self, slot: u64) -> u64 {
+        slot / self.slots_per_period()
+    }
+
+    pub fn epoch_at_slot(&self, slot: u64) -> u64 {
+        slot / self.slots_per_epoch
+    }
+
+    // UPDATE_TIMEOUT in slots
+    pub fn update_timeout(&self) -> u64 {
+        self.slots_per_period()
+    }
+
+    pub fn current_slot(&self, now: u64) -> u64 {
+        now.saturating_sub(self.genesis_time) / self.seconds_per_slot
+    }
+
+    fn fork_schedule_at_epoch(&self, epoch: u64) -> anyhow::Result<&ForkSchedule> {
+        self.forks
+            .iter()
+            .rev()
+            .find(|fork| epoch >= fork.epoch)
+            .context("no fork scheduled at genesis")
+    }
+
+    pub fn fork_at_slot(&self, slot: u64) -> anyhow::Result<Option<Fork>> {
+        Ok(self.fork_schedule_at_epoch(self.epoch_at_slot(slot))?.fork)
+    }
+
+    pub fn compute_domain(&self, domain_type: [u8; 4], fork_version: [u8; 4]) -> Root {
+        let mut version = [0; 32];
+        version[..4].copy_from_slice(&fork_version);
+
+        let fork_data_root = merkle::hash_pair(&version, &self.genesis_validators_root);
+
+        let mut domain = [0; 32];
+        domain[..4].copy_from_slice(&domain_type);
+        domain[4..].copy_from_slice(&fork_data_root[..28]);
+        domain
+    }
+}
+
+fn chunk(bytes: &[u8]) -> anyhow::Result<Root> {
+    anyhow::ensure!(
+        bytes.len() <= 32,
+        "{} bytes do not fit a chunk",
+        bytes.len()
+    );
+
+    let mut chunk = [0; 32];
+    chunk[..bytes.len()].copy_from_slice(bytes);
+    Ok(chunk)
+}
+
+// hash_tree_root of the execution payload header, from its beacon api json
+pub fn execution_payload_header_root(execution: &Value, fork: Fork) -> anyhow::Result<Root> {
+    let bytes32 = |key: &str| merkle::bytes32(bytes_field(execution, key)?);
+    let uint64 =
+        |key: &str| Ok::<_, anyhow::Error>(merkle::uint64_root(u64_field(execution, key)?));
+
+    let logs_bloom = bytes_field(execution, "logs_bloom")?;
+    let extra_data = bytes_field(execution, "extra_data")?;
+
+    let base_fee_per_gas = match execution["base_fee_per_gas"]
+        .as_str()
+        .context("base_fee_per_gas")?
+    {
+        hex if hex.starts_with("0x") => U256::from_str_radix(hex.trim_start_matches("0x"), 16)?,
+        decimal => U256::from_str_radix(decimal, 10)?,
+    };
+
+    let mut fields = vec![
+        bytes32("parent_hash")?,
+        chunk(&bytes_field(execution, "fee_recipient")?)?,
+        bytes32("state_root")?,
+        bytes32("receipts_root")?,
+        merkle::merkleize(logs_bloom.chunks(32).map(chunk).collect::<Result<_, _>>()?),
+        bytes32("prev_randao")?,
+        uint64("block_number")?,
+        uint64("gas_limit")?,
+        uint64("gas_used")?,
+        uint64("timestamp")?,
+        // ByteList[32] is a single chunk, mixed in with its length
+        merkle::hash_pair(
+            &chunk(&extra_data)?,
+            &merkle::uint64_root(extra_data.len().try_into()?),
+        ),
+        base_fee_per_gas.to_le_bytes::<32>(),
+        bytes32("block_hash")?,
+        bytes32("transactions_root")?,
+        bytes32("withdrawals_root")?,
+    ];
+
+    if fork >= Fork::Deneb {
+        fields.push(uint64("blob_gas_used")?);
+        fields.push(uint64("excess_blob_gas")?);
+    }
+
+    Ok(merkle::merkleize(fields))
+}
+
+pub fn beacon_root(header: &UnboundedLightClientHeader) -> anyhow::Result<Root> {
+    merkle::beacon_block_header_root(
+        header.beacon.slot,
+        header.beacon.proposer_index,
+        header.beacon.parent_root,
+        header.beacon.state_root,
+        header.beacon.body_root,
+    )
+}
+
+fn is_empty_header(header: &UnboundedLightClientHeader) -> anyhow::Result<bool> {
+    Ok(beacon_root(header)? == merkle::beacon_block_header_root(0, 0, [0; 32], [0; 32], [0; 32])?)
+}
+
+fn branch<B: AsRef<[u8]>>(branch: &[B]) -> anyhow::Result<Vec<Root>> {
+    branch.iter().map(merkle::bytes32).collect()
+}
+
+fn is_zero_branch<B: AsRef<[u8]>>(branch: &[B]) -> bool {
+    branch
+        .iter()
+        .all(|node| node.as_ref().iter().all(|byte| *byte == 0))
+}
+
+fn is_empty_sync_committee(sync_committee: &UnboundedSyncCommittee) -> bool {
+    is_zero_branch(&sync_committee.pubkeys) && is_zero_branch(&[sync_committee.aggregate_pubkey])
+}
+
+pub fn sync_committee_root(sync_committee: &UnboundedSyncCommittee) -> anyhow::Result<Root> {
+    merkle::sync_committee_root(&sync_committee.pubkeys, sync_committee.aggregate_pubkey)
+}
+
+pub fn is_sync_committee_update(update: &UnboundedLightClientUpdate) -> bool {
+    update
+        .next_sync_committee_branch
+        .as_ref()
+        .is_some_and(|branch| !is_zero_branch(branch))
+}
+
+pub fn is_finality_update(update: &UnboundedLightClientUpdate) -> bool {
+    !is_zero_branch(&update.finality_branch)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncAggregateBits {
+    pub bits: Vec<u8>,
+    pub size: usize,
+}
+
+impl SyncAggregateBits {
+    pub fn from_update(update: &UnboundedLightClientUpdate) -> anyhow::Result<Self> {
+        let bits = bytes_field(
+            &serde_json::to_value(&update.sync_aggregate)?,
+            "sync_committee_bits",
+        )?;
+
+        Ok(Self {
+            size: bits.len() * 8,
+            bits,
+        })
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        (self.bits[index / 8] >> (index % 8)) & 1 == 1
+    }
+
+    pub fn active_participants(&self) -> u64 {
+        self.bits
+            .iter()
+            .map(|byte| u64::from(byte.count_ones()))
+            .sum()
+    }
+
+    pub fn has_supermajority(&self) -> bool {
+        self.active_participants() * 3 >= u64::try_from(self.size).unwrap_or(u64::MAX) * 2
+    }
+}
+
+// everything is_better_update looks at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateRank {
+    pub active_participants: u64,
+    pub has_supermajority: bool,
+    pub is_sync_committee_update: bool,
+    pub is_finality_update: bool,
+    pub attested_slot: u64,
+    pub finalized_slot: u64,
+    pub signature_slot: u64,
+}
+
+impl UpdateRank {
+    pub fn from_update(update: &UnboundedLightClientUpdate) -> anyhow::Result<Self> {
+        let bits = SyncAggregateBits::from_update(update)?;
+
+        Ok(Self {
+            active_participants: bits.active_participants(),
+            has_supermajority: bits.has_supermajority(),
+            is_sync_committee_update: is_sync_committee_update(update),
+            is_finality_update: is_finality_update(update),
+            attested_slot: update.attested_header.beacon.slot,
+            finalized_slot: update.finalized_header.beacon.slot,
+            signature_slot: update.signature_slot,
+        })
+    }
+
+    // is_better_update from the sync protocol
+    pub fn is_better_than(&self, old: &UpdateRank, slots_per_period: u64) -> bool {
+        let period = |slot: u64| slot / slots_per_period;
+
+        // compare supermajority (> 2/3) sync committee participation
+        if self.has_supermajority != old.has_supermajority {
+            return self.has_supermajority;
+        }
+        if !self.has_supermajority && self.active_participants != old.active_participants {
+            return self.active_participants > old.active_participants;
+        }
+
+        // compare presence of relevant sync committee
+        let has_relevant_sync_committee = |rank: &UpdateRank| {
+            rank.is_sync_committee_update
+                && period(rank.attested_slot) == period(rank.signature_slot)
+        };
+        if has_relevant_sync_committee(self) != has_relevant_sync_committee(old) {
+            return has_relevant_sync_committee(self);
+        }
+
+        // compare indication of any finality
+        if self.is_finality_update != old.is_finality_update {
+            return self.is_finality_update;
+        }
+
+        // compare sync committee finality
+        if self.is_finality_update {
+            let has_sync_committee_finality =
+                |rank: &UpdateRank| period(rank.finalized_slot) == period(rank.attested_slot);
+            if has_sync_committee_finality(self) != has_sync_committee_finality(old) {
+                return has_sync_committee_finality(self);
+            }
+        }
+
+        // tiebreaker 1: sync committee participation beyond supermajority
+        if self.active_participants != old.active_participants {
+            return self.active_participants > old.active_participants;
+        }
+
+        // tiebreaker 2: prefer older data (fewer changes to best)
+        if self.attested_slot != old.attested_slot {
+            return self.attested_slot < old.attested_slot;
+        }
+
+        self.signature_slot < old.signature_slot
+    }
+}
+
+// the consensus spec LightClientStore, driven by the same updates the relayer relays
+pub struct LightClientStore<V: BlsVerifier = Blst> {
+    pub config: LightClientConfig,
+    pub finalized_header: UnboundedLightClientHeader,
+    pub current_sync_committee: UnboundedSyncCommittee,
+    pub next_sync_committee: Option<UnboundedSyncCommittee>,
+    pub best_valid_update: Option<UnboundedLightClientUpdate>,
+    pub optimistic_header: UnboundedLightClientHeader,
+    pub previous_max_active_participants: u64,
+    pub current_max_active_participants: u64,
+    pub verifier: V,
+}
+
+impl LightClientStore<Blst> {
+    pub fn initialize(
+        config: LightClientConfig,
+        trusted_block_root: Root,
+        bootstrap: UnboundedLightClientBootstrap,
+    ) -> anyhow::Result<Self> {
+        Self::initialize_with_verifier(config, trusted_block_root, bootstrap, Blst)
+    }
+}
+
+impl<V: BlsVerifier> LightClientStore<V> {
+    // initialize_light_client_store
+    pub fn initialize_with_verifier(
+        config: LightClientConfig,
+        trusted_block_root: Root,
+        bootstrap: UnboundedLightClientBootstrap,
+        verifier: V,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            is_valid_light_client_header(&config, &bootstrap.header)?,
+            "invalid bootstrap header",
+        );
+        anyhow::ensure!(
+            beacon_root(&bootstrap.header)? == trusted_block_root,
+            "bootstrap header does not match the trusted block root",
+        );
+        anyhow::ensure!(
+            merkle::is_valid_merkle_branch(
+                sync_committee_root(&bootstrap.current_sync_committee)?,
+                &branch(&bootstrap.current_sync_committee_branch)?,
+                merkle::CURRENT_SYNC_COMMITTEE_SUBTREE_INDEX,
+                merkle::bytes32(bootstrap.header.beacon.state_root)?,
+            ),
+            "invalid current sync committee branch",
+        );
+
+        Ok(Self {
+            config,
+            finalized_header: bootstrap.header.clone(),
+            current_sync_committee: bootstrap.current_sync_committee,
+            next_sync_committee: None,
+            best_valid_update: None,
+            optimistic_header: bootstrap.header,
+            previous_max_active_participants: 0,
+            current_max_active_participants: 0,
+            verifier,
+        })
+    }
+
+    pub fn is_next_sync_committee_known(&self) -> bool {
+        self.next_sync_committee.is_some()
+    }
+
+    pub fn safety_threshold(&self) -> u64 {
+        self.previous_max_active_participants
+            .max(self.current_max_active_participants)
+            / 2
+    }
+
+    fn finalized_period(&self) -> u64 {
+        self.config
+            .period_at_slot(self.finalized_header.beacon.slot)
+    }
+
+    pub fn validate_light_client_update(
+        &self,
+        update: &UnboundedLightClientUpdate,
+        current_slot: u64,
+    ) -> anyhow::Result<()> {
+        let config = &self.config;
+
+        let bits = SyncAggregateBits::from_update(update)?;
+
+        anyhow::ensure!(
+            bits.active_participants() >= config.min_sync_committee_participants,
+            "not enough sync committee participants",
+        );
+        anyhow::ensure!(
+            is_valid_light_client_header(config, &update.attested_header)?,
+            "invalid attested header",
+        );
+
+        let attested_slot = update.attested_header.beacon.slot;
+        let finalized_slot = update.finalized_header.beacon.slot;
+
+        anyhow::ensure!(
+            current_slot >= update.signature_slot
+                && update.signature_slot > attested_slot
+                && attested_slot >= finalized_slot,
+            "invalid slots: current {}, signature {}, attested {}, finalized {}",
+            current_slot,
+            update.signature_slot,
+            attested_slot,
+            finalized_slot,
+        );
+
+        let store_period = self.finalized_period();
+        let signature_period = config.period_at_slot(update.signature_slot);
+
+        if self.is_next_sync_committee_known() {
+            anyhow::ensure!(
+                signature_period == store_period || signature_period == store_period + 1,
+                "signature period {} is not {} or the next one",
+                signature_period,
+                store_period,
+            );
+        } else {
+            anyhow::ensure!(
+                signature_period == store_period,
+                "signature period {} is not {}",
+                signature_period,
+                store_period,
+            );
+        }
+
+        // verify update is relevant
+        let attested_period = config.period_at_slot(attested_slot);
+        let has_next_sync_committee = !self.is_next_sync_committee_known()
+            && is_sync_committee_update(update)
+            && attested_period == store_period;
+
+        anyhow::ensure!(
+            attested_slot > self.finalized_header.beacon.slot || has_next_sync_committee,
+            "update is not relevant",
+        );
+
+        // verify that the finality branch, if present, confirms the finalized header
+        if !is_finality_update(update) {
+            anyhow::ensure!(
+                is_empty_header(&update.finalized_header)?,
+                "finalized header without a finality branch",
+            );
+        } else {
+            let finalized_root = if finalized_slot == GENESIS_SLOT {
+                anyhow::ensure!(is_empty_header(&update.finalized_header)?);
+                [0; 32]
+            } else {
+                anyhow::ensure!(
+                    is_valid_light_client_header(config, &update.finalized_header)?,
+                    "invalid finalized header",
+                );
+                beacon_root(&update.finalized_header)?
+            };
+
+            anyhow::ensure!(
+                merkle::is_valid_merkle_branch(
+                    finalized_root,
+                    &branch(&update.finality_branch)?,
+                    merkle::FINALIZED_ROOT_SUBTREE_INDEX,
+                    merkle::bytes32(update.attested_header.beacon.state_root)?,
+                ),
+                "invalid finality branch",
+            );
+        }
+
+        // verify that the next sync committee, if present, matches the attested state
+        if !is_sync_committee_update(update) {
+            anyhow::ensure!(
+                update
+                    .next_sync_committee
+                    .as_ref()
+                    .is_none_or(is_empty_sync_committee),
+                "next sync committee without a next sync committee branch",
+            );
+        } else {
+            let next_sync_committee = update
+                .next_sync_committee
+                .as_ref()
+                .context("sync committee update without a next sync committee")?;
+
+            if attested_period == store_period {
+                if let Some(known) = &self.next_sync_committee {
+                    anyhow::ensure!(
+                        sync_committee_root(known)? == sync_committee_root(next_sync_committee)?,
+                        "next sync committee does not match the known one",
+                    );
+                }
+            }
+
+            anyhow::ensure!(
+                merkle::is_valid_merkle_branch(
+                    sync_committee_root(next_sync_committee)?,
+                    &branch(
+                        update
+                            .next_sync_committee_branch
+                            .as_deref()
+                            .unwrap_or_default()
+                    )?,
+                    merkle::NEXT_SYNC_COMMITTEE_SUBTREE_INDEX,
+                    merkle::bytes32(update.attested_header.beacon.state_root)?,
+                ),
+                "invalid next sync committee branch",
+            );
+        }
+
+        // verify sync committee aggregate signature
+        let sync_committee = if signature_period == store_period {
+            &self.current_sync_committee
+        } else {
+            self.next_sync_committee
+                .as_ref()
+                .context("next sync committee is unknown")?
+        };
+
+        anyhow::ensure!(
+            sync_committee.pubkeys.len() == bits.size,
+            "{} sync committee bits for {} public keys",
+            bits.size,
+            sync_committee.pubkeys.len(),
+        );
+
+        let participants = sync_committee
+            .pubkeys
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| bits.get(*index))
+            .map(|(_, public_key)| public_key.as_ref())
+            .collect::<Vec<&[u8]>>();
+
+        let fork_version_slot = update.signature_slot.max(1) - 1;
+        let fork_version = config
+            .fork_schedule_at_epoch(config.epoch_at_slot(fork_version_slot))?
+            .version;
+
+        let domain = config.compute_domain(DOMAIN_SYNC_COMMITTEE, fork_version);
+        let signing_root = merkle::hash_pair(&beacon_root(&update.attested_header)?, &domain);
+
+        let signature = bytes_field(
+            &serde_json::to_value(&update.sync_aggregate)?,
+            "sync_committee_signature",
+        )?;
+
+        anyhow::ensure!(
+            self.verifier
+                .fast_aggregate_verify(&participants, &signing_root, &signature)?,
+            "invalid sync committee signature",
+        );
+
+        Ok(())
+    }
+
+    pub fn apply_light_client_update(
+        &mut self,
+        update: UnboundedLightClientUpdate,
+    ) -> anyhow::Result<()> {
+        let store_period = self.finalized_period();
+        let finalized_period = self
+            .config
+            .period_at_slot(update.finalized_header.beacon.slot);
+
+        if !self.is_next_sync_committee_known() {
+            anyhow::ensure!(
+                finalized_period == store_period,
+                "finalized period {} is not {}",
+                finalized_period,
+                store_period,
+            );
+            self.next_sync_committee = update.next_sync_committee;
+        } else if finalized_period == store_period + 1 {
+            self.current_sync_committee = self
+                .next_sync_committee
+                .take()
+                .context("next sync committee is unknown")?;
+            self.next_sync_committee = update.next_sync_committee;
+            self.previous_max_active_participants = self.current_max_active_participants;
+            self.current_max_active_participants = 0;
+        }
+
+        if update.finalized_header.beacon.slot > self.finalized_header.beacon.slot {
+            self.finalized_header = update.finalized_header;
+
+            if self.finalized_header.beacon.slot > self.optimistic_header.beacon.slot {
+                self.optimistic_header = self.finalized_header.clone();
+            }
+        }
+
+        Ok(())
+    }
+
+    // process_light_client_store_force_update, returns whether an update was forced
+    pub fn force_update(&mut self, current_slot: u64) -> anyhow::Result<bool> {
+        if current_slot <= self.finalized_header.beacon.slot + self.config.update_timeout() {
+            return Ok(false);
+        }
+
+        let Some(mut best_valid_update) = self.best_valid_update.take() else {
+            return Ok(false);
+        };
+
+        // forced best updates may not contain finality proofs, treat the attested header as finalized
+        if best_valid_update.finalized_header.beacon.slot <= self.finalized_header.beacon.slot {
+            best_valid_update.finalized_header = best_valid_update.attested_header.clone();
+        }
+
+        self.apply_light_client_update(best_valid_update)?;
+
+        Ok(true)
+    }
+
+    pub fn process_light_client_update(
+        &mut self,
+        update: UnboundedLightClientUpdate,
+        current_slot: u64,
+    ) -> anyhow::Result<()> {
+        self.validate_light_client_update(&update, current_slot)?;
+
+        let bits = SyncAggregateBits::from_update(&update)?;
+        let slots_per_period = self.config.slots_per_period();
+
+        // update the best update in case we have to force-update to it if the timeout elapses
+        let is_best = match &self.best_valid_update {
+            None => true,
+            Some(best_valid_update) => UpdateRank::from_update(&update)?.is_better_than(
+                &UpdateRank::from_update(best_valid_update)?,
+                slots_per_period,
+            ),
+        };
+
+        if is_best {
+            self.best_valid_update = Some(update.clone());
+        }
+
+        // track the maximum number of active participants in the committee signatures
+        self.current_max_active_participants = self
+            .current_max_active_participants
+            .max(bits.active_participants());
+
+        // update the optimistic header
+        if bits.active_participants() > self.safety_threshold()
+            && update.attested_header.beacon.slot > self.optimistic_header.beacon.slot
+        {
+            self.optimistic_header = update.attested_header.clone();
+        }
+
+        // update finalized header
+        let has_finalized_next_sync_committee = !self.is_next_sync_committee_known()
+            && is_sync_committee_update(&update)
+            && is_finality_update(&update)
+            && self
+                .config
+                .period_at_slot(update.finalized_header.beacon.slot)
+                == self
+                    .config
+                    .period_at_slot(update.attested_header.beacon.slot);
+
+        if bits.has_supermajority()
+            && (update.finalized_header.beacon.slot > self.finalized_header.beacon.slot
+                || has_finalized_next_sync_committee)
+        {
+            // normal update through 2/3 threshold
+            self.apply_light_client_update(update)?;
+            self.best_valid_update = None;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn is_valid_light_client_header(
+    config: &LightClientConfig,
+    header: &UnboundedLightClientHeader,
+) -> anyhow::Result<bool> {
+    let Some(fork) = config
+        .fork_at_slot(header.beacon.slot)?
+        .filter(|fork| *fork >= Fork::Capella)
+    else {
+        // before capella the header carries no execution payload
+        return Ok(is_zero_branch(&header.execution_branch));
+    };
+
+    let execution = serde_json::to_value(&header.execution)?;
+
+    Ok(merkle::is_valid_merkle_branch(
+        execution_payload_header_root(&execution, fork)?,
+        &branch(&header.execution_branch)?,
+        EXECUTION_PAYLOAD_SUBTREE_INDEX,
+        merkle::bytes32(header.beacon.body_root)?,
+    ))
+}
@@ -1,3 +1,103 @@
-fn main() {
-    println!("Hello, world!");
+use core::net::SocketAddr;
+use std::sync::Arc;
+
+use sol_e2e::endpoint::Endpoint;
+use sol_e2e::relayer::metrics::Metrics;
+use sol_e2e::relayer::rpc::RpcServer;
+use sol_e2e::relayer::Relayer;
+use tokio_util::sync::CancellationToken;
+use unionlabs::ethereum::config::{Mainnet, Minimal};
+
+// clear of the el (8545, 8551) and beacon api (5052) defaults this usually runs next to
+const DEFAULT_RPC_LISTEN: &str = "127.0.0.1:8080";
+
+fn env<T: core::str::FromStr>(name: &str) -> anyhow::Result<T>
+where
+    T::Err: core::fmt::Display,
+{
+    std::env::var(name)
+        .map_err(|_| anyhow::anyhow!("missing {}", name))?
+        .parse()
+        .map_err(|err| anyhow::anyhow!("invalid {}: {}", name, err))
+}
+
+fn optional_env<T: core::str::FromStr>(name: &str) -> anyhow::Result<Option<T>>
+where
+    T::Err: core::fmt::Display,
+{
+    std::env::var(name).ok().map(|_| env(name)).transpose()
+}
+
+fn endpoint(prefix: &str) -> anyhow::Result<Endpoint> {
+    Endpoint::from_env(prefix)?.ok_or_else(|| anyhow::anyhow!("missing {}_ENDPOINT", prefix))
+}
+
+// serves the relayer json-rpc api, configured like the env network of the tests
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .init();
+
+    let listen: SocketAddr = optional_env("RPC_LISTEN")?.unwrap_or(DEFAULT_RPC_LISTEN.parse()?);
+
+    let cancel = CancellationToken::new();
+
+    // /metrics is always served next to the rpc, METRICS_LISTEN adds a dedicated listener
+    let metrics = Arc::new(Metrics::new()?);
+
+    if let Some(metrics_listen) = optional_env::<SocketAddr>("METRICS_LISTEN")? {
+        metrics
+            .clone()
+            .spawn(metrics_listen, cancel.clone())
+            .await?;
+    }
+
+    let preset = optional_env::<String>("PRESET")?;
+
+    let (_, handle) = match preset.as_deref() {
+        Some("mainnet") => {
+            RpcServer::new(
+                Relayer::<Mainnet>::builder()
+                    .ibc_handler_address(env("IBC_HANDLER_ADDRESS")?)
+                    .cl_endpoint(endpoint("CL")?)
+                    .el_endpoint(endpoint("EL")?)
+                    .maybe_proof_window(optional_env("EL_PROOF_WINDOW")?)
+                    .maybe_archive_el_endpoint(Endpoint::from_env("ARCHIVE_EL")?)
+                    .metrics(metrics.clone())
+                    .build(),
+            )
+            .spawn(listen, cancel.clone())
+            .await?
+        }
+        None | Some("minimal") => {
+            RpcServer::new(
+                Relayer::<Minimal>::builder()
+                    .ibc_handler_address(env("IBC_HANDLER_ADDRESS")?)
+                    .cl_endpoint(endpoint("CL")?)
+                    .el_endpoint(endpoint("EL")?)
+                    .maybe_proof_window(optional_env("EL_PROOF_WINDOW")?)
+                    .maybe_archive_el_endpoint(Endpoint::from_env("ARCHIVE_EL")?)
+                    .metrics(metrics.clone())
+                    .build(),
+            )
+            .spawn(listen, cancel.clone())
+            .await?
+        }
+        // a typo would otherwise relay with the wrong committee size
+        Some(preset) => anyhow::bail!("unknown PRESET {}, expected minimal or mainnet", preset),
+    };
+
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => {
+            result?;
+            cancel.cancel();
+        }
+        () = cancel.cancelled() => {}
+    }
+
+    handle.await?
 }
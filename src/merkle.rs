@@ -0,0 +1,112 @@
+use anyhow::Context;
+use sha2::{Digest, Sha256};
+
+// subtree indices of the light client gindices, the depth follows from the branch length
+// (the gindices moved down one level in electra, the indices stayed the same)
+pub const FINALIZED_ROOT_SUBTREE_INDEX: u64 = 41;
+pub const CURRENT_SYNC_COMMITTEE_SUBTREE_INDEX: u64 = 22;
+pub const NEXT_SYNC_COMMITTEE_SUBTREE_INDEX: u64 = 23;
+
+pub type Root = [u8; 32];
+
+pub fn bytes32(bytes: impl AsRef<[u8]>) -> anyhow::Result<Root> {
+    let bytes = bytes.as_ref();
+    bytes
+        .try_into()
+        .with_context(|| format!("expected 32 bytes, got {}", bytes.len()))
+}
+
+pub fn hash_pair(left: &Root, right: &Root) -> Root {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+pub fn uint64_root(value: u64) -> Root {
+    let mut chunk = [0; 32];
+    chunk[..8].copy_from_slice(&value.to_le_bytes());
+    chunk
+}
+
+// merkleize chunks padded with zero chunks to the next power of two
+pub fn merkleize(mut chunks: Vec<Root>) -> Root {
+    if chunks.is_empty() {
+        return [0; 32];
+    }
+
+    chunks.resize(chunks.len().next_power_of_two(), [0; 32]);
+
+    while chunks.len() > 1 {
+        chunks = chunks
+            .chunks_exact(2)
+            .map(|pair| hash_pair(&pair[0], &pair[1]))
+            .collect();
+    }
+
+    chunks[0]
+}
+
+pub fn bls_public_key_root(public_key: impl AsRef<[u8]>) -> anyhow::Result<Root> {
+    let public_key = public_key.as_ref();
+
+    anyhow::ensure!(
+        public_key.len() == 48,
+        "expected a 48 byte bls public key, got {}",
+        public_key.len()
+    );
+
+    let mut chunks = [[0; 32]; 2];
+    chunks[0].copy_from_slice(&public_key[..32]);
+    chunks[1][..16].copy_from_slice(&public_key[32..]);
+
+    Ok(hash_pair(&chunks[0], &chunks[1]))
+}
+
+pub fn beacon_block_header_root(
+    slot: u64,
+    proposer_index: u64,
+    parent_root: impl AsRef<[u8]>,
+    state_root: impl AsRef<[u8]>,
+    body_root: impl AsRef<[u8]>,
+) -> anyhow::Result<Root> {
+    Ok(merkleize(vec![
+        uint64_root(slot),
+        uint64_root(proposer_index),
+        bytes32(parent_root)?,
+        bytes32(state_root)?,
+        bytes32(body_root)?,
+    ]))
+}
+
+pub fn sync_committee_root<P: AsRef<[u8]>>(
+    public_keys: impl IntoIterator<Item = P>,
+    aggregate_public_key: impl AsRef<[u8]>,
+) -> anyhow::Result<Root> {
+    let public_keys_root = merkleize(
+        public_keys
+            .into_iter()
+            .map(bls_public_key_root)
+            .collect::<Result<_, _>>()?,
+    );
+
+    Ok(hash_pair(
+        &public_keys_root,
+        &bls_public_key_root(aggregate_public_key)?,
+    ))
+}
+
+pub fn is_valid_merkle_branch(leaf: Root, branch: &[Root], index: u64, root: Root) -> bool {
+    let computed = branch
+        .iter()
+        .enumerate()
+        .fold(leaf, |value, (depth, node)| {
+            if (index >> depth) & 1 == 1 {
+                hash_pair(node, &value)
+            } else {
+                hash_pair(&value, node)
+            }
+        });
+
+    computed == root
+}
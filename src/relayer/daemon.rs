@@ -0,0 +1,170 @@
+use core::future::Future;
+use core::time::Duration;
+use std::time::SystemTime;
+
+use bon::Builder;
+use futures::future::Either;
+use tokio_util::sync::CancellationToken;
+use unionlabs::ethereum::config::{
+    BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES, SYNC_COMMITTEE_SIZE,
+};
+use unionlabs::ibc::lightclients::ethereum::header::Header;
+use unionlabs::ibc::lightclients::ethereum::trusted_sync_committee::TrustedSyncCommittee;
+
+use crate::relayer::optimistic::{OptimisticHeader, RelayedHeader};
+use crate::relayer::Relayer;
+
+pub trait Counterparty<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES>:
+    Send
+{
+    // latest slot the counterparty client trusts
+    fn latest_height(&self) -> impl Future<Output = anyhow::Result<u64>> + Send;
+    // timestamp of the latest consensus state, in nanos
+    fn latest_timestamp(&self) -> impl Future<Output = anyhow::Result<u64>> + Send;
+    // one header per call, so a failed batch is known to have applied every header before it
+    fn submit(&mut self, header: Header<C>) -> impl Future<Output = anyhow::Result<()>> + Send;
+    // counterparties that only follow finality reject these
+    fn submit_optimistic(
+        &mut self,
+        _header: OptimisticHeader<C>,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send {
+        async {
+            Err(anyhow::anyhow!(
+                "counterparty only accepts finalized headers"
+            ))
+        }
+    }
+
+    fn submit_relayed(
+        &mut self,
+        header: RelayedHeader<C>,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send {
+        match header {
+            RelayedHeader::Finalized(header) => Either::Left(self.submit(header)),
+            RelayedHeader::Optimistic(header) => Either::Right(self.submit_optimistic(header)),
+        }
+    }
+}
+
+#[derive(Builder)]
+pub struct Daemon<C, P>
+where
+    C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES,
+    P: Counterparty<C>,
+{
+    pub relayer: Relayer<C>,
+    pub counterparty: P,
+    // must match the counterparty's latest height
+    pub trusted_sync_committee: TrustedSyncCommittee<C>,
+    pub trusting_period: Duration,
+    // update once the counterparty falls this many slots behind finality
+    #[builder(default = 32)]
+    pub max_lag: u64,
+    // update once the latest consensus state is this close to expiry
+    #[builder(default = Duration::from_secs(60 * 60))]
+    pub expiry_margin: Duration,
+    #[builder(default = Duration::from_secs(6))]
+    pub poll_interval: Duration,
+}
+
+impl<C, P> Daemon<C, P>
+where
+    C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES,
+    P: Counterparty<C>,
+{
+    // returns whether the counterparty was updated
+    #[tracing::instrument(skip(self))]
+    pub async fn tick(&mut self) -> anyhow::Result<bool> {
+        let latest_height = self.counterparty.latest_height().await?;
+        let latest_timestamp = self.counterparty.latest_timestamp().await?;
+
+        anyhow::ensure!(
+            latest_height == self.trusted_sync_committee.trusted_height.revision_height,
+            "counterparty is at {} but the daemon trusts {}",
+            latest_height,
+            self.trusted_sync_committee.trusted_height.revision_height,
+        );
+
+        let finalized_slot = self
+            .relayer
+            .finality_update()
+            .await?
+            .finalized_header
+            .beacon
+            .slot;
+
+        if finalized_slot <= latest_height {
+            return Ok(false);
+        }
+
+        let now: u64 = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_nanos()
+            .try_into()?;
+
+        let expires_at = latest_timestamp
+            .saturating_add(self.trusting_period.as_nanos().try_into()?)
+            .saturating_sub(self.expiry_margin.as_nanos().try_into()?);
+
+        let lagging = finalized_slot - latest_height > self.max_lag;
+        let expiring = now >= expires_at;
+
+        if !lagging && !expiring {
+            return Ok(false);
+        }
+
+        tracing::info!(
+            latest_height,
+            finalized_slot,
+            lagging,
+            expiring,
+            "updating counterparty"
+        );
+
+        let (headers, trusted_sync_committee) = self
+            .relayer
+            .header(self.trusted_sync_committee.clone())
+            .await?;
+
+        self.submit_headers(headers, trusted_sync_committee).await?;
+
+        Ok(true)
+    }
+
+    // every header is built on the trust the one before it established, so the trust follows
+    // each accepted header and still matches the counterparty when a later one fails
+    pub async fn submit_headers(
+        &mut self,
+        headers: Vec<Header<C>>,
+        trusted_sync_committee: TrustedSyncCommittee<C>,
+    ) -> anyhow::Result<()> {
+        let mut headers = headers.into_iter().peekable();
+
+        while let Some(header) = headers.next() {
+            self.counterparty.submit(header).await?;
+
+            self.trusted_sync_committee = match headers.peek() {
+                Some(next) => next.trusted_sync_committee.clone(),
+                None => trusted_sync_committee.clone(),
+            };
+        }
+
+        Ok(())
+    }
+
+    // an in-flight update always completes before shutting down
+    pub async fn run(mut self, cancel: CancellationToken) -> Self {
+        while !cancel.is_cancelled() {
+            if let Err(err) = self.tick().await {
+                tracing::warn!(?err, "daemon tick failed");
+            }
+
+            tokio::select! {
+                () = cancel.cancelled() => {}
+                () = tokio::time::sleep(self.poll_interval) => {}
+            }
+        }
+
+        self
+    }
+}
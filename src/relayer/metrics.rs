@@ -0,0 +1,198 @@
+use core::future::IntoFuture;
+use core::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use prometheus::{
+    exponential_buckets, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, Opts, Registry, TextEncoder,
+};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::relayer::OutsideProofWindow;
+
+pub const METRICS_PATH: &str = "/metrics";
+
+// every relayer owns a registry, so concurrent relayers in one process don't share counters
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    pub headers_built: IntCounter,
+    pub updates_per_call: Histogram,
+    // finalized slot minus trusted slot of the last header call
+    pub trusted_slot_lag: IntGauge,
+    // beacon and execution requests made by the relayer, by endpoint
+    pub request_duration: HistogramVec,
+    // json-rpc requests served by the relayer, by method
+    pub rpc_duration: HistogramVec,
+    // bytes of every account and storage proof node
+    pub proof_size: Histogram,
+    pub errors: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> anyhow::Result<Self> {
+        let registry = Registry::new_custom(Some("relayer".to_string()), None)?;
+
+        let headers_built = IntCounter::new("headers_built_total", "headers built")?;
+
+        let updates_per_call = Histogram::with_opts(
+            HistogramOpts::new("updates_per_call", "light client updates per header call")
+                .buckets(vec![0.0, 1.0, 2.0, 3.0, 5.0, 8.0, 13.0, 21.0]),
+        )?;
+
+        let trusted_slot_lag = IntGauge::new(
+            "trusted_slot_lag",
+            "slots between the trusted and the finalized slot",
+        )?;
+
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "request_duration_seconds",
+                "beacon and execution request latency",
+            ),
+            &["endpoint"],
+        )?;
+
+        let rpc_duration = HistogramVec::new(
+            HistogramOpts::new("rpc_duration_seconds", "json-rpc request latency"),
+            &["method"],
+        )?;
+
+        let proof_size = Histogram::with_opts(
+            HistogramOpts::new("proof_size_bytes", "account and storage proof size")
+                .buckets(exponential_buckets(256.0, 2.0, 12)?),
+        )?;
+
+        let errors = IntCounterVec::new(
+            Opts::new("errors_total", "relayer errors"),
+            &["call", "kind"],
+        )?;
+
+        registry.register(Box::new(headers_built.clone()))?;
+        registry.register(Box::new(updates_per_call.clone()))?;
+        registry.register(Box::new(trusted_slot_lag.clone()))?;
+        registry.register(Box::new(request_duration.clone()))?;
+        registry.register(Box::new(rpc_duration.clone()))?;
+        registry.register(Box::new(proof_size.clone()))?;
+        registry.register(Box::new(errors.clone()))?;
+
+        Ok(Self {
+            registry,
+            headers_built,
+            updates_per_call,
+            trusted_slot_lag,
+            request_duration,
+            rpc_duration,
+            proof_size,
+            errors,
+        })
+    }
+
+    // prometheus text exposition format
+    pub fn encode(&self) -> anyhow::Result<String> {
+        Ok(TextEncoder::new().encode_to_string(&self.registry.gather())?)
+    }
+
+    pub fn error(&self, call: &str, err: &anyhow::Error) {
+        self.errors
+            .with_label_values(&[call, error_kind(err)])
+            .inc();
+    }
+
+    // alloy's rpc calls are IntoFuture rather than Future
+    pub async fn time<T>(&self, endpoint: &str, f: impl IntoFuture<Output = T>) -> T {
+        let _timer = self
+            .request_duration
+            .with_label_values(&[endpoint])
+            .start_timer();
+
+        f.await
+    }
+
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new()
+            .route(METRICS_PATH, get(handle_metrics))
+            .with_state(self)
+    }
+
+    // binds `listen` (port 0 picks a free one) and serves /metrics until cancelled
+    pub async fn spawn(
+        self: Arc<Self>,
+        listen: SocketAddr,
+        cancel: CancellationToken,
+    ) -> anyhow::Result<(SocketAddr, JoinHandle<anyhow::Result<()>>)> {
+        let listener = tokio::net::TcpListener::bind(listen).await?;
+        let local_addr = listener.local_addr()?;
+
+        tracing::info!(%local_addr, "relayer metrics listening");
+
+        let router = self.router();
+
+        let handle = tokio::spawn(async move {
+            axum::serve(listener, router)
+                .with_graceful_shutdown(async move { cancel.cancelled().await })
+                .await?;
+
+            Ok(())
+        });
+
+        Ok((local_addr, handle))
+    }
+}
+
+async fn handle_metrics(State(metrics): State<Arc<Metrics>>) -> impl IntoResponse {
+    match metrics.encode() {
+        Ok(body) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+            body,
+        ),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            [(header::CONTENT_TYPE, "text/plain")],
+            format!("{:#}", err),
+        ),
+    }
+}
+
+// coarse enough to keep the label cardinality bounded
+pub fn error_kind(err: &anyhow::Error) -> &'static str {
+    if err.downcast_ref::<OutsideProofWindow>().is_some() {
+        "outside_proof_window"
+    } else if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+        if err.is_timeout() {
+            "timeout"
+        } else if err.is_status() {
+            "http_status"
+        } else {
+            "http"
+        }
+    } else if err.downcast_ref::<serde_json::Error>().is_some() {
+        "decode"
+    } else {
+        "other"
+    }
+}
+
+// beacon api paths with their slots, periods and roots stripped
+pub fn endpoint(path: &str) -> String {
+    path.split('?')
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .map(|segment| {
+            if segment.starts_with("0x") || segment.parse::<u64>().is_ok() {
+                ":id"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -1,17 +1,28 @@
-use core::net::SocketAddr;
+use core::future::IntoFuture;
+use std::sync::Arc;
+use std::time::SystemTime;
 
-use alloy::primitives::Address;
-use alloy::providers::{Provider, ProviderBuilder};
+use alloy::primitives::{keccak256, Address};
+use alloy::providers::{Provider, ProviderBuilder, RootProvider};
+use alloy::transports::BoxTransport;
 use anyhow::Context;
-use beacon_api::client::{BeaconApiClient, BlockId};
+use beacon_api::types::Spec;
+use bon::Builder;
 use ics008_wasm_client::MerklePath;
+use prost::Message;
 use protos::union::ibc::lightclients::ethereum::v1::{
-    LightClientUpdate as LightClientUpdateProto, SyncCommittee as SyncCommitteeProto,
+    LightClientUpdate as LightClientUpdateProto, StorageProof as StorageProofProto,
+    SyncCommittee as SyncCommitteeProto,
 };
+use serde::de::DeserializeOwned;
+use unionlabs::ethereum::beacon::genesis_data::GenesisData;
+use unionlabs::ethereum::beacon::light_client_bootstrap::UnboundedLightClientBootstrap;
+use unionlabs::ethereum::beacon::light_client_finality_update::UnboundedLightClientFinalityUpdate;
 use unionlabs::ethereum::config::{
     BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES, SYNC_COMMITTEE_SIZE,
 };
 use unionlabs::ethereum::{ibc_commitment_key_v2, IBC_HANDLER_COMMITMENTS_SLOT};
+use unionlabs::hash::H256;
 use unionlabs::ibc::core::client::height::Height;
 use unionlabs::ibc::lightclients::ethereum::account_proof::AccountProof;
 use unionlabs::ibc::lightclients::ethereum::account_update::AccountUpdate;
@@ -21,28 +32,424 @@ use unionlabs::ibc::lightclients::ethereum::header::Header;
 use unionlabs::ibc::lightclients::ethereum::light_client_update::UnboundedLightClientUpdate;
 use unionlabs::ibc::lightclients::ethereum::misbehaviour::Misbehaviour;
 use unionlabs::ibc::lightclients::ethereum::storage_proof::StorageProof;
+use unionlabs::ibc::lightclients::ethereum::sync_committee::{
+    SyncCommittee, UnboundedSyncCommittee,
+};
 use unionlabs::ibc::lightclients::ethereum::trusted_sync_committee::{
     ActiveSyncCommittee, TrustedSyncCommittee,
 };
 use unionlabs::uint::U256;
 
+use crate::endpoint::Endpoint;
+use crate::light_client::{is_sync_committee_update, LightClientConfig, LightClientStore};
+use crate::merkle;
+use crate::relayer::metrics::Metrics;
+
+pub mod daemon;
+pub mod metrics;
+pub mod optimistic;
+pub mod rpc;
+pub mod ssz;
+
+// the commitment path is the concatenation of every key_path element with no separator,
+// as the IBC v2 handler hashes it: ["ab", "c"] and ["a", "bc"] are the same path
+pub fn commitment_key(merkle_path: &MerklePath) -> anyhow::Result<U256> {
+    anyhow::ensure!(!merkle_path.key_path.is_empty(), "key_path is empty");
+
+    Ok(ibc_commitment_key_v2(
+        merkle_path.key_path.concat().into(),
+        IBC_HANDLER_COMMITMENTS_SLOT,
+    ))
+}
+
+// the ssz transport decodes into beacon api json, so both transports share the json types
+fn from_json<T: DeserializeOwned>(value: serde_json::Value) -> anyhow::Result<T> {
+    Ok(serde_json::from_value(value)?)
+}
+
+#[derive(Debug, Clone)]
+pub struct MembershipProof {
+    pub slot: u64,
+    pub path: MerklePath,
+    pub storage_proof: StorageProof,
+    // raw value, its keccak256 is what the handler stores
+    pub value: Vec<u8>,
+    // proto encoded StorageProof, as verify_membership expects it
+    pub proof: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NonMembershipProof {
+    pub slot: u64,
+    pub path: MerklePath,
+    pub storage_proof: StorageProof,
+    // proto encoded StorageProof, as verify_non_membership expects it
+    pub proof: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutsideProofWindow {
+    pub execution_height: u64,
+    pub latest_height: u64,
+    pub proof_window: u64,
+}
+
+impl core::fmt::Display for OutsideProofWindow {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "execution height {} is outside of the el proof window of {} blocks (latest height {}) and no archive endpoint is configured",
+            self.execution_height, self.proof_window, self.latest_height,
+        )
+    }
+}
+
+impl core::error::Error for OutsideProofWindow {}
+
+#[derive(Builder)]
 pub struct Relayer<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES> {
     pub ibc_handler_address: Address,
-    pub cl_socket: SocketAddr,
-    pub el_socket: SocketAddr,
+    #[builder(into)]
+    pub cl_endpoint: Endpoint,
+    #[builder(into)]
+    pub el_endpoint: Endpoint,
+    // how many blocks behind the tip el_endpoint serves eth_getProof for
+    pub proof_window: Option<u64>,
+    // serves eth_getProof for anything outside of the proof window
+    #[builder(into)]
+    pub archive_el_endpoint: Option<Endpoint>,
+    // fetch light client data as ssz, falling back to json
+    #[builder(default = true)]
+    pub ssz_transport: bool,
+    // shared with whoever serves /metrics, nothing is recorded without it
+    pub metrics: Option<Arc<Metrics>>,
+    // clients are created once and shared by every call, including concurrent ones
+    #[builder(skip)]
+    provider: tokio::sync::OnceCell<RootProvider<BoxTransport>>,
+    #[builder(skip)]
+    archive_provider: tokio::sync::OnceCell<RootProvider<BoxTransport>>,
+    #[builder(skip)]
+    http: reqwest::Client,
+    #[builder(default)]
     pub _phantom: core::marker::PhantomData<C>,
 }
 
 impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES> Relayer<C> {
-    pub async fn beacon_client(&self) -> anyhow::Result<BeaconApiClient> {
-        Ok(BeaconApiClient::new(format!("http://{}", self.cl_socket)).await?)
+    // read only, so none of the transaction fillers
+    pub async fn provider(&self) -> anyhow::Result<&RootProvider<BoxTransport>> {
+        self.provider
+            .get_or_try_init(|| async {
+                Ok(ProviderBuilder::new().on_client(self.el_endpoint.rpc_client().await?))
+            })
+            .await
+    }
+
+    async fn archive_provider(
+        &self,
+        archive_el_endpoint: &Endpoint,
+    ) -> anyhow::Result<&RootProvider<BoxTransport>> {
+        self.archive_provider
+            .get_or_try_init(|| async {
+                Ok(ProviderBuilder::new().on_client(archive_el_endpoint.rpc_client().await?))
+            })
+            .await
+    }
+
+    fn sync_committee_size() -> usize {
+        <C::SYNC_COMMITTEE_SIZE as typenum::Unsigned>::USIZE
+    }
+
+    // unmetered without metrics
+    async fn time<T>(&self, endpoint: &str, f: impl IntoFuture<Output = T>) -> T {
+        match &self.metrics {
+            Some(metrics) => metrics.time(endpoint, f).await,
+            None => f.await,
+        }
+    }
+
+    async fn beacon_get(&self, path: &str, accept: &str) -> anyhow::Result<reqwest::Response> {
+        let request = self
+            .http
+            .get(self.cl_endpoint.join(path)?)
+            .headers(self.cl_endpoint.header_map()?)
+            .header(reqwest::header::ACCEPT, accept)
+            .send();
+
+        Ok(self
+            .time(&metrics::endpoint(path), request)
+            .await?
+            .error_for_status()?)
+    }
+
+    async fn beacon_json(&self, path: &str) -> anyhow::Result<serde_json::Value> {
+        let response = self.beacon_get(path, "application/json").await?;
+
+        Ok(serde_json::from_slice(&response.bytes().await?)?)
+    }
+
+    // the fork is taken from the eth-consensus-version header
+    async fn beacon_ssz(&self, path: &str) -> anyhow::Result<(Option<ssz::Fork>, Vec<u8>)> {
+        let response = self.beacon_get(path, ssz::SSZ_CONTENT_TYPE).await?;
+
+        let headers = response.headers();
+
+        anyhow::ensure!(
+            headers
+                .get(reqwest::header::CONTENT_TYPE)
+                .is_some_and(|x| x.as_bytes().starts_with(ssz::SSZ_CONTENT_TYPE.as_bytes())),
+            "{} was not served as ssz",
+            path,
+        );
+
+        let fork = headers
+            .get(ssz::CONSENSUS_VERSION_HEADER)
+            .map(|x| ssz::Fork::from_name(x.to_str()?))
+            .transpose()?;
+
+        Ok((fork, response.bytes().await?.to_vec()))
+    }
+
+    // union's beacon client only takes a url, so its types are fetched through beacon_json,
+    // which sends the endpoint's auth and headers
+    pub async fn spec(&self) -> anyhow::Result<Spec> {
+        let mut response = self.beacon_json("/eth/v1/config/spec").await?;
+
+        from_json(response["data"].take())
+    }
+
+    pub async fn genesis(&self) -> anyhow::Result<GenesisData> {
+        let mut response = self.beacon_json("/eth/v1/beacon/genesis").await?;
+
+        from_json(response["data"].take())
+    }
+
+    pub async fn block_root(&self, slot: u64) -> anyhow::Result<H256> {
+        let mut response = self
+            .beacon_json(&format!("/eth/v1/beacon/headers/{}", slot))
+            .await?;
+
+        from_json(response["data"]["root"].take())
+    }
+
+    // every fork since bellatrix has the payload at the same place in the json
+    pub async fn execution_height(&self, slot: u64) -> anyhow::Result<u64> {
+        let response = self
+            .beacon_json(&format!("/eth/v2/beacon/blocks/{}", slot))
+            .await?;
+
+        Ok(
+            response["data"]["message"]["body"]["execution_payload"]["block_number"]
+                .as_str()
+                .with_context(|| format!("block at slot {} has no execution payload", slot))?
+                .parse()?,
+        )
+    }
+
+    pub async fn fork_digests(&self) -> anyhow::Result<Vec<(ssz::ForkDigest, ssz::Fork)>> {
+        let spec = self.beacon_json("/eth/v1/config/spec").await?;
+        let genesis = self.beacon_json("/eth/v1/beacon/genesis").await?;
+
+        let genesis_validators_root = merkle::bytes32(hex::decode(
+            genesis["data"]["genesis_validators_root"]
+                .as_str()
+                .context("genesis_validators_root")?
+                .trim_start_matches("0x"),
+        )?)?;
+
+        ssz::Fork::ALL
+            .into_iter()
+            // forks unknown to the beacon node are skipped
+            .filter_map(|fork| {
+                spec["data"][fork.version_key()]
+                    .as_str()
+                    .map(|version| (fork, version))
+            })
+            .map(|(fork, version)| {
+                let version = <[u8; 4]>::try_from(hex::decode(version.trim_start_matches("0x"))?)
+                    .map_err(|x| anyhow::anyhow!("invalid fork version {:?}", x))?;
+
+                Ok((ssz::fork_digest(version, genesis_validators_root), fork))
+            })
+            .collect()
     }
 
-    pub async fn provider(&self) -> anyhow::Result<impl Provider> {
-        Ok(ProviderBuilder::new()
-            .with_recommended_fillers()
-            .on_builtin(&format!("http://{}", self.el_socket))
-            .await?)
+    pub async fn light_client_config(&self) -> anyhow::Result<LightClientConfig> {
+        let spec = self.beacon_json("/eth/v1/config/spec").await?;
+        let genesis = self.beacon_json("/eth/v1/beacon/genesis").await?;
+
+        LightClientConfig::from_spec(&spec["data"], &genesis["data"])
+    }
+
+    pub async fn light_client_updates_ssz(
+        &self,
+        start_period: u64,
+        count: u64,
+    ) -> anyhow::Result<Vec<UnboundedLightClientUpdate>> {
+        let (_, bytes) = self
+            .beacon_ssz(&format!(
+                "/eth/v1/beacon/light_client/updates?start_period={}&count={}",
+                start_period, count
+            ))
+            .await?;
+
+        let fork_digests = self.fork_digests().await?;
+
+        ssz::light_client_update_chunks(&bytes)?
+            .into_iter()
+            .map(|(digest, payload)| {
+                let fork = fork_digests
+                    .iter()
+                    .find(|(fork_digest, _)| *fork_digest == digest)
+                    .map(|(_, fork)| *fork)
+                    .with_context(|| format!("unknown fork digest {}", hex::encode(digest)))?;
+
+                from_json(ssz::light_client_update(
+                    payload,
+                    fork,
+                    Self::sync_committee_size(),
+                )?)
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn light_client_updates(
+        &self,
+        start_period: u64,
+        count: u64,
+    ) -> anyhow::Result<Vec<UnboundedLightClientUpdate>> {
+        if self.ssz_transport {
+            match self.light_client_updates_ssz(start_period, count).await {
+                Ok(updates) => return Ok(updates),
+                Err(err) => tracing::warn!(?err, "falling back to json for light client updates"),
+            }
+        }
+
+        let response = self
+            .beacon_json(&format!(
+                "/eth/v1/beacon/light_client/updates?start_period={}&count={}",
+                start_period, count
+            ))
+            .await?;
+
+        response
+            .as_array()
+            .context("light client updates are not an array")?
+            .iter()
+            .map(|update| from_json(update["data"].clone()))
+            .collect()
+    }
+
+    pub async fn bootstrap_ssz(
+        &self,
+        block_root: H256,
+    ) -> anyhow::Result<UnboundedLightClientBootstrap> {
+        let (fork, bytes) = self
+            .beacon_ssz(&format!(
+                "/eth/v1/beacon/light_client/bootstrap/{}",
+                block_root
+            ))
+            .await?;
+
+        from_json(ssz::light_client_bootstrap(
+            &bytes,
+            fork.context("missing consensus version")?,
+            Self::sync_committee_size(),
+        )?)
+    }
+
+    pub async fn bootstrap(
+        &self,
+        block_root: H256,
+    ) -> anyhow::Result<UnboundedLightClientBootstrap> {
+        if self.ssz_transport {
+            match self.bootstrap_ssz(block_root).await {
+                Ok(bootstrap) => return Ok(bootstrap),
+                Err(err) => tracing::warn!(?err, "falling back to json for bootstrap"),
+            }
+        }
+
+        let mut response = self
+            .beacon_json(&format!(
+                "/eth/v1/beacon/light_client/bootstrap/{}",
+                block_root
+            ))
+            .await?;
+
+        from_json(response["data"].take())
+    }
+
+    pub async fn finality_update_ssz(&self) -> anyhow::Result<UnboundedLightClientFinalityUpdate> {
+        let (fork, bytes) = self
+            .beacon_ssz("/eth/v1/beacon/light_client/finality_update")
+            .await?;
+
+        from_json(ssz::light_client_finality_update(
+            &bytes,
+            fork.context("missing consensus version")?,
+            Self::sync_committee_size(),
+        )?)
+    }
+
+    pub async fn finality_update(&self) -> anyhow::Result<UnboundedLightClientFinalityUpdate> {
+        if self.ssz_transport {
+            match self.finality_update_ssz().await {
+                Ok(finality_update) => return Ok(finality_update),
+                Err(err) => tracing::warn!(?err, "falling back to json for finality update"),
+            }
+        }
+
+        let mut response = self
+            .beacon_json("/eth/v1/beacon/light_client/finality_update")
+            .await?;
+
+        from_json(response["data"].take())
+    }
+
+    // the archive endpoint when `execution_height` is outside of the proof window at
+    // `latest_height`, none when el_endpoint serves it
+    pub fn archive_endpoint_for(
+        &self,
+        execution_height: u64,
+        latest_height: u64,
+    ) -> Result<Option<&Endpoint>, OutsideProofWindow> {
+        let Some(proof_window) = self.proof_window else {
+            return Ok(None);
+        };
+
+        if latest_height.saturating_sub(execution_height) <= proof_window {
+            return Ok(None);
+        }
+
+        match &self.archive_el_endpoint {
+            Some(archive_el_endpoint) => Ok(Some(archive_el_endpoint)),
+            None => Err(OutsideProofWindow {
+                execution_height,
+                latest_height,
+                proof_window,
+            }),
+        }
+    }
+
+    pub async fn proof_provider(
+        &self,
+        execution_height: u64,
+    ) -> anyhow::Result<&RootProvider<BoxTransport>> {
+        // without a window every height is served, so the tip isn't needed
+        if self.proof_window.is_none() {
+            return self.provider().await;
+        }
+
+        let provider = self.provider().await?;
+
+        let latest_height = self
+            .time("eth_blockNumber", provider.get_block_number())
+            .await?;
+
+        match self.archive_endpoint_for(execution_height, latest_height)? {
+            Some(archive_el_endpoint) => self.archive_provider(archive_el_endpoint).await,
+            None => Ok(provider),
+        }
     }
 
     pub async fn account_proof<const N: usize>(
@@ -50,35 +457,66 @@ impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTE
         slot: u64,
         merkle_paths: [MerklePath; N],
     ) -> anyhow::Result<(AccountProof, [StorageProof; N])> {
-        let beacon = self.beacon_client().await?;
-        let provider = self.provider().await?;
+        let (account_proof, storage_proofs) = self.account_proof_paths(slot, &merkle_paths).await?;
+
+        let storage_proofs = <[_; N]>::try_from(storage_proofs)
+            .map_err(|x| anyhow::anyhow!("length should be {} but got {}", N, x.len()))?;
+
+        Ok((account_proof, storage_proofs))
+    }
+
+    // account_proof for a number of paths only known at runtime
+    #[tracing::instrument(skip_all, fields(slot = slot, paths = merkle_paths.len()))]
+    pub async fn account_proof_paths(
+        &self,
+        slot: u64,
+        merkle_paths: &[MerklePath],
+    ) -> anyhow::Result<(AccountProof, Vec<StorageProof>)> {
+        let result = self.build_account_proof(slot, merkle_paths).await;
+
+        let Some(metrics) = &self.metrics else {
+            return result;
+        };
+
+        match &result {
+            Ok((account_proof, storage_proofs)) => {
+                metrics.proof_size.observe(
+                    account_proof
+                        .proof
+                        .iter()
+                        .chain(storage_proofs.iter().flat_map(|x| &x.proof))
+                        .map(Vec::len)
+                        .sum::<usize>() as f64,
+                );
+            }
+            Err(err) => metrics.error("account_proof", err),
+        }
+
+        result
+    }
+
+    async fn build_account_proof(
+        &self,
+        slot: u64,
+        merkle_paths: &[MerklePath],
+    ) -> anyhow::Result<(AccountProof, Vec<StorageProof>)> {
+        let execution_height = self.execution_height(slot).await?;
 
-        let execution_height = beacon.execution_height(BlockId::Slot(slot)).await?;
+        let provider = self.proof_provider(execution_height).await?;
 
-        let response = provider
+        let request = provider
             .get_proof(
                 self.ibc_handler_address,
                 merkle_paths
-                    .into_iter()
+                    .iter()
                     .map(|merkle_path| {
-                        merkle_path
-                            .key_path
-                            .into_iter()
-                            // take only the first key_path from a merkle path
-                            // https://github.com/gjermundgaraba/union/blob/10355e6/light-clients/ethereum-light-client/src/client.rs#L87
-                            .next()
-                            .context("key_path is empty")
-                            .map(|key| {
-                                ibc_commitment_key_v2(key.into(), IBC_HANDLER_COMMITMENTS_SLOT)
-                                    .to_be_bytes()
-                                    .into()
-                            })
+                        commitment_key(merkle_path).map(|key| key.to_be_bytes().into())
                     })
                     .collect::<Result<_, _>>()?,
             )
-            .block_id(execution_height.into())
-            .await
-            .unwrap();
+            .block_id(execution_height.into());
+
+        let response = self.time("eth_getProof", request).await?;
 
         let account_proof = AccountProof {
             storage_root: response.storage_hash.into(),
@@ -89,8 +527,16 @@ impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTE
                 .collect(),
         };
 
-        let storage_proofs = <[_; N]>::try_from(response.storage_proof)
-            .map_err(|x| anyhow::anyhow!("length should be {} but got {}", N, x.len()))?
+        anyhow::ensure!(
+            response.storage_proof.len() == merkle_paths.len(),
+            "length should be {} but got {}",
+            merkle_paths.len(),
+            response.storage_proof.len(),
+        );
+
+        let storage_proofs = response
+            .storage_proof
+            .into_iter()
             .map(|proof| StorageProof {
                 key: U256::from_be_bytes(proof.key.as_b256().0),
                 value: U256::from_limbs(proof.value.into_limbs()),
@@ -99,43 +545,216 @@ impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTE
                     .into_iter()
                     .map(|bytes| bytes.to_vec())
                     .collect(),
-            });
+            })
+            .collect();
 
         Ok((account_proof, storage_proofs))
     }
 
-    pub async fn initialize(
+    pub async fn membership_proof(
         &self,
         slot: u64,
-    ) -> anyhow::Result<(ClientState, ConsensusState, TrustedSyncCommittee<C>)> {
-        let beacon = self.beacon_client().await?;
-        let provider = self.provider().await?;
+        path: MerklePath,
+        value: Vec<u8>,
+    ) -> anyhow::Result<MembershipProof> {
+        let (_, [storage_proof]) = self.account_proof(slot, [path.clone()]).await?;
 
-        let chain_id = provider.get_chain_id().await?;
+        let commitment = U256::from_be_bytes(keccak256(&value).0);
+
+        anyhow::ensure!(
+            storage_proof.value != U256::default(),
+            "nothing is committed at {:?} at slot {}",
+            path.key_path,
+            slot,
+        );
+        anyhow::ensure!(
+            storage_proof.value == commitment,
+            "commitment at {:?} at slot {} does not match the expected value",
+            path.key_path,
+            slot,
+        );
+
+        Ok(MembershipProof {
+            slot,
+            path,
+            proof: StorageProofProto::from(storage_proof.clone()).encode_to_vec(),
+            storage_proof,
+            value,
+        })
+    }
+
+    pub async fn non_membership_proof(
+        &self,
+        slot: u64,
+        path: MerklePath,
+    ) -> anyhow::Result<NonMembershipProof> {
+        let (_, [storage_proof]) = self.account_proof(slot, [path.clone()]).await?;
 
-        let genesis = beacon.genesis().await?.data;
+        anyhow::ensure!(
+            storage_proof.value == U256::default(),
+            "a commitment exists at {:?} at slot {}",
+            path.key_path,
+            slot,
+        );
+
+        Ok(NonMembershipProof {
+            slot,
+            path,
+            proof: StorageProofProto::from(storage_proof.clone()).encode_to_vec(),
+            storage_proof,
+        })
+    }
 
-        let trusted_header = beacon.header(BlockId::Slot(slot)).await?.data;
-        let bootstrap = beacon.bootstrap(trusted_header.root).await?.data;
+    #[tracing::instrument(skip(self))]
+    pub async fn initialize(
+        &self,
+        slot: u64,
+    ) -> anyhow::Result<(ClientState, ConsensusState, TrustedSyncCommittee<C>)> {
+        let trusted_block_root = self.block_root(slot).await?;
+        let bootstrap = self.bootstrap(trusted_block_root).await?;
 
-        let spec = beacon.spec().await?.data;
+        let spec = self.spec().await?;
 
         anyhow::ensure!(bootstrap.header.beacon.slot == slot);
 
         let light_client_update = {
             let current_period = slot / spec.period();
 
-            let light_client_updates = beacon.light_client_updates(current_period, 1).await?;
+            let light_client_updates = self.light_client_updates(current_period, 1).await?;
 
-            let [update] = <[_; 1]>::try_from(light_client_updates.0)
+            let [update] = <[_; 1]>::try_from(light_client_updates)
                 .map_err(|x| anyhow::anyhow!("length should be 1 but got {}", x.len()))?;
 
-            anyhow::ensure!(update.data.finalized_header.beacon.slot <= slot);
-            anyhow::ensure!(slot - update.data.finalized_header.beacon.slot < spec.period());
+            anyhow::ensure!(update.finalized_header.beacon.slot <= slot);
+            anyhow::ensure!(slot - update.finalized_header.beacon.slot < spec.period());
 
-            update.data.clone()
+            update
         };
 
+        self.initial_state(
+            slot,
+            bootstrap.header.execution.state_root,
+            bootstrap.header.execution.timestamp,
+            SyncCommitteeProto::from(bootstrap.current_sync_committee).try_into()?,
+            light_client_update
+                .next_sync_committee
+                .map(|sync_committee| SyncCommitteeProto::from(sync_committee).try_into())
+                .transpose()?,
+        )
+        .await
+    }
+
+    // nearest epoch boundary at or before `near_slot` that the beacon node has a bootstrap for
+    #[tracing::instrument(skip(self))]
+    pub async fn checkpoint(&self, near_slot: u64) -> anyhow::Result<(u64, H256)> {
+        let spec = self.spec().await?;
+
+        let latest_epoch = near_slot / spec.slots_per_epoch;
+
+        // bootstraps are not served for anything older than a period or so
+        for epoch in (0..=latest_epoch)
+            .rev()
+            .take(spec.epochs_per_sync_committee_period.try_into()?)
+        {
+            let slot = epoch * spec.slots_per_epoch;
+
+            // the boundary slot may have been missed
+            let Ok(block_root) = self.block_root(slot).await else {
+                continue;
+            };
+
+            if self.bootstrap(block_root).await.is_ok() {
+                return Ok((slot, block_root));
+            }
+        }
+
+        anyhow::bail!("no epoch boundary with a bootstrap near slot {}", near_slot)
+    }
+
+    // seeds the client from a trusted block root, as a checkpoint sync would
+    #[tracing::instrument(skip_all, fields(slot = tracing::field::Empty))]
+    pub async fn initialize_from_checkpoint(
+        &self,
+        trusted_block_root: H256,
+    ) -> anyhow::Result<(ClientState, ConsensusState, TrustedSyncCommittee<C>)> {
+        let bootstrap = self.bootstrap(trusted_block_root).await?;
+
+        // checks the header against the root and the current committee against the header
+        let store = LightClientStore::initialize(
+            self.light_client_config().await?,
+            merkle::bytes32(trusted_block_root)?,
+            bootstrap.clone(),
+        )?;
+
+        let slot = bootstrap.header.beacon.slot;
+
+        tracing::Span::current().record("slot", slot);
+
+        let next_sync_committee = self.verified_next_sync_committee(&store).await?;
+
+        self.initial_state(
+            slot,
+            bootstrap.header.execution.state_root,
+            bootstrap.header.execution.timestamp,
+            SyncCommitteeProto::from(bootstrap.current_sync_committee).try_into()?,
+            next_sync_committee
+                .map(|sync_committee| SyncCommitteeProto::from(sync_committee).try_into())
+                .transpose()?,
+        )
+        .await
+    }
+
+    // the next committee of the store's period, from an update signed by the current committee
+    // that proves it against its attested state, or none if the period has no such update yet
+    async fn verified_next_sync_committee(
+        &self,
+        store: &LightClientStore,
+    ) -> anyhow::Result<Option<UnboundedSyncCommittee>> {
+        let period = store
+            .config
+            .period_at_slot(store.finalized_header.beacon.slot);
+
+        let current_slot = store.config.current_slot(
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .as_secs(),
+        );
+
+        for update in self.light_client_updates(period, 1).await? {
+            if store
+                .config
+                .period_at_slot(update.attested_header.beacon.slot)
+                != period
+                || !is_sync_committee_update(&update)
+            {
+                continue;
+            }
+
+            match store.validate_light_client_update(&update, current_slot) {
+                Ok(()) => return Ok(update.next_sync_committee),
+                Err(err) => tracing::warn!(?err, period, "ignoring next sync committee"),
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn initial_state(
+        &self,
+        slot: u64,
+        state_root: H256,
+        timestamp: u64,
+        current_sync_committee: SyncCommittee<C>,
+        next_sync_committee: Option<SyncCommittee<C>>,
+    ) -> anyhow::Result<(ClientState, ConsensusState, TrustedSyncCommittee<C>)> {
+        let provider = self.provider().await?;
+
+        let chain_id = provider.get_chain_id().await?;
+
+        let genesis = self.genesis().await?;
+
+        let spec = self.spec().await?;
+
         let client_state = ClientState {
             chain_id: chain_id.to_string().parse()?,
             genesis_validators_root: genesis.genesis_validators_root,
@@ -154,22 +773,16 @@ impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTE
             ibc_contract_address: self.ibc_handler_address.0 .0.into(),
         };
 
-        let account_update = self
-            .account_proof(bootstrap.header.beacon.slot, [])
-            .await?
-            .0;
+        let account_update = self.account_proof(slot, []).await?.0;
 
         let consensus_state = ConsensusState {
-            slot: bootstrap.header.beacon.slot,
-            state_root: bootstrap.header.execution.state_root,
+            slot,
+            state_root,
             storage_root: account_update.storage_root,
             // Normalize to nanos in order to be compliant with cosmos
-            timestamp: bootstrap.header.execution.timestamp * 1_000_000_000,
-            current_sync_committee: bootstrap.current_sync_committee.aggregate_pubkey,
-            next_sync_committee: light_client_update
-                .next_sync_committee
-                .clone()
-                .map(|nsc| nsc.aggregate_pubkey),
+            timestamp: timestamp * 1_000_000_000,
+            current_sync_committee: current_sync_committee.aggregate_pubkey,
+            next_sync_committee: next_sync_committee.as_ref().map(|nsc| nsc.aggregate_pubkey),
         };
 
         let trusted_sync_committee = TrustedSyncCommittee {
@@ -177,29 +790,33 @@ impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTE
                 revision_number: 0,
                 revision_height: slot,
             },
-            sync_committee: if let Some(sync_committee) = light_client_update.next_sync_committee {
-                ActiveSyncCommittee::Next(SyncCommitteeProto::from(sync_committee).try_into()?)
+            sync_committee: if let Some(sync_committee) = next_sync_committee {
+                ActiveSyncCommittee::Next(sync_committee)
             } else {
-                ActiveSyncCommittee::Current(
-                    SyncCommitteeProto::from(bootstrap.current_sync_committee).try_into()?,
-                )
+                ActiveSyncCommittee::Current(current_sync_committee)
             },
         };
 
         Ok((client_state, consensus_state, trusted_sync_committee))
     }
 
-    pub async fn header(
+    // the updates that take a client from `trusted_slot` to the latest finalized slot, in order
+    #[tracing::instrument(
+        skip(self),
+        fields(
+            target_slot = tracing::field::Empty,
+            trusted_period = tracing::field::Empty,
+            target_period = tracing::field::Empty,
+            updates = tracing::field::Empty,
+        )
+    )]
+    pub async fn update_sequence(
         &self,
-        mut trusted_sync_committee: TrustedSyncCommittee<C>,
-    ) -> anyhow::Result<(Vec<Header<C>>, TrustedSyncCommittee<C>)> {
-        let beacon = self.beacon_client().await?;
-
-        let spec = beacon.spec().await?.data;
-
-        let trusted_slot = trusted_sync_committee.trusted_height.revision_height;
+        trusted_slot: u64,
+    ) -> anyhow::Result<Vec<UnboundedLightClientUpdate>> {
+        let spec = self.spec().await?;
 
-        let latest_finalized_update = beacon.finality_update().await?.data;
+        let latest_finalized_update = self.finality_update().await?;
 
         let target_slot = latest_finalized_update.finalized_header.beacon.slot;
 
@@ -212,21 +829,22 @@ impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTE
 
         let target_period = target_slot / spec.period();
 
-        let light_client_updates = beacon
+        tracing::Span::current()
+            .record("target_slot", target_slot)
+            .record("trusted_period", trusted_period)
+            .record("target_period", target_period);
+
+        let mut light_client_updates = self
             .light_client_updates(trusted_period, target_period - trusted_period + 1)
             .await?
-            .0
             .into_iter()
-            .map(|x| x.data)
             .filter(|x| {
                 trusted_slot < x.finalized_header.beacon.slot
                     && x.finalized_header.beacon.slot <= target_slot
             })
             .collect::<Vec<_>>();
 
-        let mut headers = if light_client_updates.is_empty() {
-            vec![]
-        } else {
+        if !light_client_updates.is_empty() {
             anyhow::ensure!(
                 light_client_updates
                     .first()
@@ -248,65 +866,14 @@ impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTE
                         .slot
                     < spec.period()
             );
+        }
 
-            let mut headers = Vec::with_capacity(light_client_updates.len());
-
-            for update in light_client_updates {
-                let new_trusted_sync_committee = TrustedSyncCommittee {
-                    trusted_height: Height {
-                        revision_number: 0,
-                        revision_height: update.finalized_header.beacon.slot,
-                    },
-                    sync_committee: if let Some(sync_committee) =
-                        update.next_sync_committee.as_ref()
-                    {
-                        ActiveSyncCommittee::Next(
-                            SyncCommitteeProto::from(sync_committee.clone()).try_into()?,
-                        )
-                    } else {
-                        ActiveSyncCommittee::Current(
-                            trusted_sync_committee.sync_committee.get().clone(),
-                        )
-                    },
-                };
-
-                let account_update = AccountUpdate {
-                    account_proof: self
-                        .account_proof(update.finalized_header.beacon.slot, [])
-                        .await?
-                        .0,
-                };
-
-                let consensus_update = LightClientUpdateProto::from(update).try_into()?;
-
-                headers.push(Header {
-                    trusted_sync_committee,
-                    consensus_update,
-                    account_update,
-                });
-
-                trusted_sync_committee = new_trusted_sync_committee;
-            }
-
-            headers
-        };
-
-        if !headers
+        if !light_client_updates
             .last()
-            .map(|x| x.consensus_update.finalized_header.beacon.slot == target_slot)
+            .map(|x| x.finalized_header.beacon.slot == target_slot)
             .unwrap_or_default()
         {
-            let new_trusted_sync_committee = TrustedSyncCommittee {
-                trusted_height: Height {
-                    revision_number: 0,
-                    revision_height: target_slot,
-                },
-                sync_committee: ActiveSyncCommittee::Current(
-                    trusted_sync_committee.sync_committee.get().clone(),
-                ),
-            };
-
-            let update = UnboundedLightClientUpdate {
+            light_client_updates.push(UnboundedLightClientUpdate {
                 attested_header: latest_finalized_update.attested_header,
                 next_sync_committee: None,
                 next_sync_committee_branch: None,
@@ -314,14 +881,96 @@ impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTE
                 finality_branch: latest_finalized_update.finality_branch,
                 sync_aggregate: latest_finalized_update.sync_aggregate,
                 signature_slot: latest_finalized_update.signature_slot,
-            };
+            });
+        }
 
-            let consensus_update = LightClientUpdateProto::from(update).try_into()?;
+        tracing::Span::current().record("updates", light_client_updates.len());
+
+        Ok(light_client_updates)
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            trusted_slot = trusted_sync_committee.trusted_height.revision_height,
+            headers = tracing::field::Empty,
+        )
+    )]
+    pub async fn header(
+        &self,
+        trusted_sync_committee: TrustedSyncCommittee<C>,
+    ) -> anyhow::Result<(Vec<Header<C>>, TrustedSyncCommittee<C>)> {
+        let trusted_slot = trusted_sync_committee.trusted_height.revision_height;
+
+        let result = self.build_headers(trusted_sync_committee).await;
+
+        let Some(metrics) = &self.metrics else {
+            return result;
+        };
+
+        match &result {
+            Ok((headers, _)) => {
+                metrics.headers_built.inc_by(headers.len() as u64);
+
+                if let Some(header) = headers.last() {
+                    metrics.trusted_slot_lag.set(
+                        header
+                            .consensus_update
+                            .finalized_header
+                            .beacon
+                            .slot
+                            .saturating_sub(trusted_slot) as i64,
+                    );
+                }
+            }
+            Err(err) => metrics.error("header", err),
+        }
+
+        result
+    }
+
+    async fn build_headers(
+        &self,
+        mut trusted_sync_committee: TrustedSyncCommittee<C>,
+    ) -> anyhow::Result<(Vec<Header<C>>, TrustedSyncCommittee<C>)> {
+        let light_client_updates = self
+            .update_sequence(trusted_sync_committee.trusted_height.revision_height)
+            .await?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics
+                .updates_per_call
+                .observe(light_client_updates.len() as f64);
+        }
+
+        let mut headers = Vec::with_capacity(light_client_updates.len());
+
+        for update in light_client_updates {
+            let new_trusted_sync_committee = TrustedSyncCommittee {
+                trusted_height: Height {
+                    revision_number: 0,
+                    revision_height: update.finalized_header.beacon.slot,
+                },
+                sync_committee: if let Some(sync_committee) = update.next_sync_committee.as_ref() {
+                    ActiveSyncCommittee::Next(
+                        SyncCommitteeProto::from(sync_committee.clone()).try_into()?,
+                    )
+                } else {
+                    ActiveSyncCommittee::Current(
+                        trusted_sync_committee.sync_committee.get().clone(),
+                    )
+                },
+            };
 
             let account_update = AccountUpdate {
-                account_proof: self.account_proof(target_slot, []).await?.0,
+                account_proof: self
+                    .account_proof(update.finalized_header.beacon.slot, [])
+                    .await?
+                    .0,
             };
 
+            let consensus_update = LightClientUpdateProto::from(update).try_into()?;
+
             headers.push(Header {
                 trusted_sync_committee,
                 consensus_update,
@@ -331,15 +980,18 @@ impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTE
             trusted_sync_committee = new_trusted_sync_committee;
         }
 
+        tracing::Span::current().record("headers", headers.len());
+
         Ok((headers, trusted_sync_committee))
     }
 
+    // would need two conflicting updates signed by the same committee, which the beacon api
+    // doesn't serve (/eth/v1/beacon/pool/attester_slashings is about attestations), so a
+    // caller gets an error it can handle instead of a panic
     pub async fn misbehaviour(&self) -> anyhow::Result<Misbehaviour<C>> {
-        let _beacon = self.beacon_client().await?;
-        let _provider = self.provider().await?;
-
-        // /eth/v1/beacon/pool/attester_slashings
-
-        unimplemented!()
+        anyhow::bail!(
+            "building misbehaviour from {} is not supported",
+            self.cl_endpoint
+        )
     }
 }
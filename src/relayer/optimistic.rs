@@ -0,0 +1,197 @@
+use anyhow::Context;
+use futures::{Stream, StreamExt, TryStreamExt};
+use serde_json::Value;
+use unionlabs::ethereum::beacon::sync_aggregate::UnboundedSyncAggregate;
+use unionlabs::ethereum::config::{
+    BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES, SYNC_COMMITTEE_SIZE,
+};
+use unionlabs::ibc::lightclients::ethereum::account_update::AccountUpdate;
+use unionlabs::ibc::lightclients::ethereum::header::Header;
+use unionlabs::ibc::lightclients::ethereum::light_client_header::UnboundedLightClientHeader;
+use unionlabs::ibc::lightclients::ethereum::trusted_sync_committee::TrustedSyncCommittee;
+
+use crate::relayer::{from_json, ssz, Relayer};
+
+pub const OPTIMISTIC_UPDATE_TOPIC: &str = "light_client_optimistic_update";
+pub const OPTIMISTIC_UPDATE_PATH: &str = "/eth/v1/beacon/light_client/optimistic_update";
+
+// an attested header signed by the sync committee, nothing about it is finalized
+#[derive(Debug, Clone)]
+pub struct OptimisticUpdate {
+    pub attested_header: UnboundedLightClientHeader,
+    pub sync_aggregate: UnboundedSyncAggregate,
+    pub signature_slot: u64,
+}
+
+impl OptimisticUpdate {
+    // beacon api json, as served by REST and SSE or decoded from ssz
+    pub fn from_json(mut value: Value) -> anyhow::Result<Self> {
+        Ok(Self {
+            attested_header: from_json(value["attested_header"].take())?,
+            sync_aggregate: from_json(value["sync_aggregate"].take())?,
+            signature_slot: value["signature_slot"]
+                .as_str()
+                .context("signature_slot")?
+                .parse()?,
+        })
+    }
+
+    pub fn slot(&self) -> u64 {
+        self.attested_header.beacon.slot
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OptimisticHeader<
+    C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES,
+> {
+    pub trusted_sync_committee: TrustedSyncCommittee<C>,
+    pub optimistic_update: OptimisticUpdate,
+    pub account_update: AccountUpdate,
+}
+
+// headers handed to a counterparty, tagged with how final they are
+#[derive(Debug, Clone)]
+pub enum RelayedHeader<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES>
+{
+    Finalized(Header<C>),
+    // may be reorged out, only for counterparties that accept optimistic heights
+    Optimistic(OptimisticHeader<C>),
+}
+
+impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES>
+    RelayedHeader<C>
+{
+    pub fn slot(&self) -> u64 {
+        match self {
+            RelayedHeader::Finalized(header) => {
+                header.consensus_update.finalized_header.beacon.slot
+            }
+            RelayedHeader::Optimistic(header) => header.optimistic_update.slot(),
+        }
+    }
+
+    pub fn is_finalized(&self) -> bool {
+        matches!(self, RelayedHeader::Finalized(_))
+    }
+}
+
+// (event, data) pairs of a text/event-stream body
+fn server_sent_events<B: AsRef<[u8]>>(
+    stream: impl Stream<Item = reqwest::Result<B>> + Send + 'static,
+) -> impl Stream<Item = anyhow::Result<(String, String)>> + Send {
+    futures::stream::try_unfold(
+        (stream.boxed(), String::new()),
+        |(mut stream, mut buffer)| async move {
+            loop {
+                if let Some(end) = buffer.find("\n\n") {
+                    let block = buffer.drain(..end + 2).collect::<String>();
+
+                    let mut event = String::from("message");
+                    let mut data = vec![];
+
+                    for line in block.lines() {
+                        if let Some(value) = line.strip_prefix("event:") {
+                            event = value.trim().to_string();
+                        } else if let Some(value) = line.strip_prefix("data:") {
+                            data.push(value.trim_start());
+                        }
+                    }
+
+                    return Ok::<_, anyhow::Error>(Some((
+                        (event, data.join("\n")),
+                        (stream, buffer),
+                    )));
+                }
+
+                match stream.try_next().await? {
+                    Some(chunk) => {
+                        buffer.push_str(&String::from_utf8_lossy(chunk.as_ref()).replace('\r', ""))
+                    }
+                    None => return Ok(None),
+                }
+            }
+        },
+    )
+}
+
+impl<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES> Relayer<C> {
+    pub async fn optimistic_update_ssz(&self) -> anyhow::Result<OptimisticUpdate> {
+        let (fork, bytes) = self.beacon_ssz(OPTIMISTIC_UPDATE_PATH).await?;
+
+        OptimisticUpdate::from_json(ssz::light_client_optimistic_update(
+            &bytes,
+            fork.context("missing consensus version")?,
+            Self::sync_committee_size(),
+        )?)
+    }
+
+    pub async fn optimistic_update(&self) -> anyhow::Result<OptimisticUpdate> {
+        if self.ssz_transport {
+            match self.optimistic_update_ssz().await {
+                Ok(optimistic_update) => return Ok(optimistic_update),
+                Err(err) => tracing::warn!(?err, "falling back to json for optimistic update"),
+            }
+        }
+
+        let mut response = self.beacon_json(OPTIMISTIC_UPDATE_PATH).await?;
+
+        OptimisticUpdate::from_json(response["data"].take())
+    }
+
+    // every optimistic update the beacon node gossips, as it arrives
+    pub async fn optimistic_updates(
+        &self,
+    ) -> anyhow::Result<impl Stream<Item = anyhow::Result<OptimisticUpdate>> + Send> {
+        let response = self
+            .http
+            .get(self.cl_endpoint.join("/eth/v1/events")?)
+            .headers(self.cl_endpoint.header_map()?)
+            .query(&[("topics", OPTIMISTIC_UPDATE_TOPIC)])
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(
+            server_sent_events(response.bytes_stream()).try_filter_map(
+                |(event, data)| async move {
+                    if event != OPTIMISTIC_UPDATE_TOPIC {
+                        return Ok(None);
+                    }
+
+                    let mut value = serde_json::from_str::<Value>(&data)?;
+
+                    OptimisticUpdate::from_json(value["data"].take()).map(Some)
+                },
+            ),
+        )
+    }
+
+    // the header is not finalized, the counterparty has to accept optimistic heights
+    #[tracing::instrument(skip_all, fields(slot = optimistic_update.slot()))]
+    pub async fn optimistic_header(
+        &self,
+        trusted_sync_committee: TrustedSyncCommittee<C>,
+        optimistic_update: OptimisticUpdate,
+    ) -> anyhow::Result<RelayedHeader<C>> {
+        let trusted_slot = trusted_sync_committee.trusted_height.revision_height;
+
+        anyhow::ensure!(
+            trusted_slot < optimistic_update.slot(),
+            "trusted slot {} must be less than the optimistic slot {}",
+            trusted_slot,
+            optimistic_update.slot(),
+        );
+
+        let account_update = AccountUpdate {
+            account_proof: self.account_proof(optimistic_update.slot(), []).await?.0,
+        };
+
+        Ok(RelayedHeader::Optimistic(OptimisticHeader {
+            trusted_sync_committee,
+            optimistic_update,
+            account_update,
+        }))
+    }
+}
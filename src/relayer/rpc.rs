@@ -0,0 +1,387 @@
+use core::net::SocketAddr;
+use std::sync::Arc;
+
+use alloy::providers::Provider;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use ics008_wasm_client::MerklePath;
+use prost::Message;
+use protos::union::ibc::lightclients::ethereum::v1::{
+    AccountProof as AccountProofProto, ClientState as ClientStateProto,
+    ConsensusState as ConsensusStateProto, Header as HeaderProto,
+    StorageProof as StorageProofProto,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use unionlabs::ethereum::config::{
+    BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES, SYNC_COMMITTEE_SIZE,
+};
+use unionlabs::ibc::lightclients::ethereum::header::Header;
+use unionlabs::ibc::lightclients::ethereum::trusted_sync_committee::TrustedSyncCommittee;
+
+use crate::relayer::Relayer;
+
+pub const JSONRPC_VERSION: &str = "2.0";
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const SERVER_ERROR: i64 = -32000;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+fn rpc_error(code: i64, message: impl ToString) -> RpcError {
+    RpcError {
+        code,
+        message: message.to_string(),
+    }
+}
+
+// proto encodes as 0x prefixed hex of the protobuf bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    #[default]
+    Json,
+    Proto,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InitializeParams {
+    pub slot: u64,
+    #[serde(default)]
+    pub encoding: Encoding,
+}
+
+// the trusted sync committee is relayer state, it is always passed around as json
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeaderParams {
+    pub trusted_sync_committee: Value,
+    #[serde(default)]
+    pub encoding: Encoding,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountProofParams {
+    pub slot: u64,
+    // key_path of every merkle path to prove
+    #[serde(default)]
+    pub paths: Vec<Vec<String>>,
+    #[serde(default)]
+    pub encoding: Encoding,
+}
+
+fn encode<T: Serialize, P: Message + From<T>>(
+    value: T,
+    encoding: Encoding,
+) -> anyhow::Result<Value> {
+    Ok(match encoding {
+        Encoding::Json => serde_json::to_value(value)?,
+        Encoding::Proto => {
+            Value::String(format!("0x{}", hex::encode(P::from(value).encode_to_vec())))
+        }
+    })
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|err| rpc_error(INVALID_PARAMS, err))
+}
+
+fn server_error(err: anyhow::Error) -> RpcError {
+    rpc_error(SERVER_ERROR, format!("{:#}", err))
+}
+
+pub struct RpcServer<C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES> {
+    pub relayer: Arc<Relayer<C>>,
+}
+
+impl<C> RpcServer<C>
+where
+    C: Clone
+        + SYNC_COMMITTEE_SIZE
+        + BYTES_PER_LOGS_BLOOM
+        + MAX_EXTRA_DATA_BYTES
+        + Send
+        + Sync
+        + 'static,
+    TrustedSyncCommittee<C>: Serialize + DeserializeOwned,
+    Header<C>: Serialize,
+{
+    pub fn new(relayer: Relayer<C>) -> Self {
+        Self {
+            relayer: Arc::new(relayer),
+        }
+    }
+
+    pub fn router(&self) -> Router {
+        let router = Router::new()
+            .route("/", post(Self::handle_rpc))
+            .route("/health", get(Self::handle_health))
+            .with_state(self.relayer.clone());
+
+        match &self.relayer.metrics {
+            Some(metrics) => router.merge(metrics.clone().router()),
+            None => router,
+        }
+    }
+
+    // binds `listen` (port 0 picks a free one) and serves until cancelled
+    pub async fn spawn(
+        &self,
+        listen: SocketAddr,
+        cancel: CancellationToken,
+    ) -> anyhow::Result<(SocketAddr, JoinHandle<anyhow::Result<()>>)> {
+        let listener = tokio::net::TcpListener::bind(listen).await?;
+        let local_addr = listener.local_addr()?;
+
+        tracing::info!(%local_addr, "relayer rpc listening");
+
+        let router = self.router();
+
+        let handle = tokio::spawn(async move {
+            axum::serve(listener, router)
+                .with_graceful_shutdown(async move { cancel.cancelled().await })
+                .await?;
+
+            Ok(())
+        });
+
+        Ok((local_addr, handle))
+    }
+
+    async fn handle_health(State(relayer): State<Arc<Relayer<C>>>) -> (StatusCode, Json<Value>) {
+        let health = health(&relayer).await;
+
+        let status = if health["healthy"].as_bool().unwrap_or_default() {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+
+        (status, Json(health))
+    }
+
+    async fn handle_rpc(State(relayer): State<Arc<Relayer<C>>>, body: Bytes) -> Json<RpcResponse> {
+        let request = match serde_json::from_slice::<RpcRequest>(&body) {
+            Ok(request) => request,
+            Err(err) => {
+                let code = if serde_json::from_slice::<Value>(&body).is_ok() {
+                    INVALID_REQUEST
+                } else {
+                    PARSE_ERROR
+                };
+
+                return Json(RpcResponse::new(Value::Null, Err(rpc_error(code, err))));
+            }
+        };
+
+        if request.jsonrpc != JSONRPC_VERSION {
+            return Json(RpcResponse::new(
+                request.id,
+                Err(rpc_error(INVALID_REQUEST, "jsonrpc must be 2.0")),
+            ));
+        }
+
+        let method = match request.method.as_str() {
+            method @ ("initialize" | "header" | "account_proof" | "health") => method,
+            _ => "unknown",
+        };
+
+        let timer = relayer.metrics.as_ref().map(|metrics| {
+            metrics
+                .rpc_duration
+                .with_label_values(&[method])
+                .start_timer()
+        });
+
+        let result = dispatch(&relayer, &request.method, request.params).await;
+
+        if let Some(timer) = timer {
+            timer.observe_duration();
+        }
+
+        Json(RpcResponse::new(request.id, result))
+    }
+}
+
+async fn health<C>(relayer: &Relayer<C>) -> Value
+where
+    C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES,
+{
+    let beacon = relayer
+        .finality_update()
+        .await
+        .map(|x| x.finalized_header.beacon.slot);
+
+    let execution = match relayer.provider().await {
+        Ok(provider) => provider
+            .get_block_number()
+            .await
+            .map_err(anyhow::Error::from),
+        Err(err) => Err(err),
+    };
+
+    json!({
+        "healthy": beacon.is_ok() && execution.is_ok(),
+        "finalized_slot": beacon.as_ref().ok(),
+        "block_number": execution.as_ref().ok(),
+        "errors": [beacon.err(), execution.err()]
+            .into_iter()
+            .flatten()
+            .map(|err| format!("{:#}", err))
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[tracing::instrument(skip(relayer, params))]
+async fn dispatch<C>(relayer: &Relayer<C>, method: &str, params: Value) -> Result<Value, RpcError>
+where
+    C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES,
+    TrustedSyncCommittee<C>: Serialize + DeserializeOwned,
+    Header<C>: Serialize,
+{
+    match method {
+        "initialize" => {
+            let InitializeParams { slot, encoding } = parse_params(params)?;
+
+            initialize(relayer, slot, encoding)
+                .await
+                .map_err(server_error)
+        }
+        "header" => {
+            let HeaderParams {
+                trusted_sync_committee,
+                encoding,
+            } = parse_params(params)?;
+
+            let trusted_sync_committee = parse_params(trusted_sync_committee)?;
+
+            header(relayer, trusted_sync_committee, encoding)
+                .await
+                .map_err(server_error)
+        }
+        "account_proof" => {
+            let AccountProofParams {
+                slot,
+                paths,
+                encoding,
+            } = parse_params(params)?;
+
+            let merkle_paths = paths
+                .into_iter()
+                .map(|key_path| MerklePath { key_path })
+                .collect::<Vec<_>>();
+
+            account_proof(relayer, slot, &merkle_paths, encoding)
+                .await
+                .map_err(server_error)
+        }
+        "health" => Ok(health(relayer).await),
+        method => Err(rpc_error(
+            METHOD_NOT_FOUND,
+            format!("unknown method {}", method),
+        )),
+    }
+}
+
+async fn initialize<C>(relayer: &Relayer<C>, slot: u64, encoding: Encoding) -> anyhow::Result<Value>
+where
+    C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES,
+    TrustedSyncCommittee<C>: Serialize,
+{
+    let (client_state, consensus_state, trusted_sync_committee) = relayer.initialize(slot).await?;
+
+    Ok(json!({
+        "client_state": encode::<_, ClientStateProto>(client_state, encoding)?,
+        "consensus_state": encode::<_, ConsensusStateProto>(consensus_state, encoding)?,
+        "trusted_sync_committee": serde_json::to_value(trusted_sync_committee)?,
+    }))
+}
+
+async fn header<C>(
+    relayer: &Relayer<C>,
+    trusted_sync_committee: TrustedSyncCommittee<C>,
+    encoding: Encoding,
+) -> anyhow::Result<Value>
+where
+    C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES,
+    TrustedSyncCommittee<C>: Serialize,
+    Header<C>: Serialize,
+{
+    let (headers, trusted_sync_committee) = relayer.header(trusted_sync_committee).await?;
+
+    Ok(json!({
+        "headers": headers
+            .into_iter()
+            .map(|header| encode::<_, HeaderProto>(header, encoding))
+            .collect::<Result<Vec<_>, _>>()?,
+        "trusted_sync_committee": serde_json::to_value(trusted_sync_committee)?,
+    }))
+}
+
+async fn account_proof<C>(
+    relayer: &Relayer<C>,
+    slot: u64,
+    merkle_paths: &[MerklePath],
+    encoding: Encoding,
+) -> anyhow::Result<Value>
+where
+    C: Clone + SYNC_COMMITTEE_SIZE + BYTES_PER_LOGS_BLOOM + MAX_EXTRA_DATA_BYTES,
+{
+    let (account_proof, storage_proofs) = relayer.account_proof_paths(slot, merkle_paths).await?;
+
+    Ok(json!({
+        "account_proof": encode::<_, AccountProofProto>(account_proof, encoding)?,
+        "storage_proofs": storage_proofs
+            .into_iter()
+            .map(|storage_proof| encode::<_, StorageProofProto>(storage_proof, encoding))
+            .collect::<Result<Vec<_>, _>>()?,
+    }))
+}
@@ -0,0 +1,393 @@
+use alloy::primitives::U256;
+use anyhow::Context;
+use serde_json::{json, Value};
+
+use crate::merkle;
+
+pub const SSZ_CONTENT_TYPE: &str = "application/octet-stream";
+pub const CONSENSUS_VERSION_HEADER: &str = "eth-consensus-version";
+
+const BYTES_PER_PUBKEY: usize = 48;
+const BYTES_PER_SIGNATURE: usize = 96;
+const BYTES_PER_LOGS_BLOOM: usize = 256;
+const BEACON_BLOCK_HEADER_LEN: usize = 112;
+const EXECUTION_BRANCH_LEN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Fork {
+    Altair,
+    Bellatrix,
+    Capella,
+    Deneb,
+    Electra,
+}
+
+impl Fork {
+    pub const ALL: [Fork; 5] = [
+        Fork::Altair,
+        Fork::Bellatrix,
+        Fork::Capella,
+        Fork::Deneb,
+        Fork::Electra,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Fork::Altair => "altair",
+            Fork::Bellatrix => "bellatrix",
+            Fork::Capella => "capella",
+            Fork::Deneb => "deneb",
+            Fork::Electra => "electra",
+        }
+    }
+
+    pub fn from_name(name: &str) -> anyhow::Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|fork| fork.name().eq_ignore_ascii_case(name))
+            .with_context(|| format!("unknown fork {}", name))
+    }
+
+    // key of the fork version in /eth/v1/config/spec
+    pub fn version_key(&self) -> String {
+        format!("{}_FORK_VERSION", self.name().to_uppercase())
+    }
+
+    fn sync_committee_branch_len(&self) -> usize {
+        if *self >= Fork::Electra {
+            6
+        } else {
+            5
+        }
+    }
+
+    fn finality_branch_len(&self) -> usize {
+        if *self >= Fork::Electra {
+            7
+        } else {
+            6
+        }
+    }
+}
+
+pub type ForkDigest = [u8; 4];
+
+// compute_fork_digest from the consensus specs
+pub fn fork_digest(fork_version: [u8; 4], genesis_validators_root: merkle::Root) -> ForkDigest {
+    let mut version = [0; 32];
+    version[..4].copy_from_slice(&fork_version);
+
+    let fork_data_root = merkle::hash_pair(&version, &genesis_validators_root);
+
+    let mut digest = [0; 4];
+    digest.copy_from_slice(&fork_data_root[..4]);
+    digest
+}
+
+fn hex(bytes: &[u8]) -> String {
+    format!("0x{}", ::hex::encode(bytes))
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + len)
+            .with_context(|| {
+                format!(
+                    "unexpected end of ssz input at {} reading {} of {} bytes",
+                    self.position,
+                    len,
+                    self.bytes.len()
+                )
+            })?;
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn hex(&mut self, len: usize) -> anyhow::Result<Value> {
+        Ok(Value::String(hex(self.take(len)?)))
+    }
+
+    fn u64(&mut self) -> anyhow::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    // uint64 is a decimal string in the beacon api json
+    fn u64_string(&mut self) -> anyhow::Result<Value> {
+        Ok(Value::String(self.u64()?.to_string()))
+    }
+
+    fn offset(&mut self) -> anyhow::Result<usize> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?).try_into()?)
+    }
+
+    fn branch(&mut self, len: usize) -> anyhow::Result<Value> {
+        (0..len)
+            .map(|_| self.hex(32))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array)
+    }
+
+    fn ensure_fixed_end(&self, first_offset: usize) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.position == first_offset,
+            "fixed part ends at {} but the first offset is {}",
+            self.position,
+            first_offset
+        );
+        Ok(())
+    }
+
+    fn variable(&self, start: usize, end: usize) -> anyhow::Result<&'a [u8]> {
+        self.bytes
+            .get(start..end)
+            .with_context(|| format!("invalid ssz offsets {}..{}", start, end))
+    }
+}
+
+pub fn beacon_block_header(bytes: &[u8]) -> anyhow::Result<Value> {
+    anyhow::ensure!(bytes.len() == BEACON_BLOCK_HEADER_LEN);
+
+    let mut decoder = Decoder::new(bytes);
+
+    Ok(json!({
+        "slot": decoder.u64_string()?,
+        "proposer_index": decoder.u64_string()?,
+        "parent_root": decoder.hex(32)?,
+        "state_root": decoder.hex(32)?,
+        "body_root": decoder.hex(32)?,
+    }))
+}
+
+pub fn execution_payload_header(bytes: &[u8], fork: Fork) -> anyhow::Result<Value> {
+    anyhow::ensure!(
+        fork >= Fork::Capella,
+        "{} has no execution header",
+        fork.name()
+    );
+
+    let mut decoder = Decoder::new(bytes);
+
+    let mut header = json!({
+        "parent_hash": decoder.hex(32)?,
+        "fee_recipient": decoder.hex(20)?,
+        "state_root": decoder.hex(32)?,
+        "receipts_root": decoder.hex(32)?,
+        "logs_bloom": decoder.hex(BYTES_PER_LOGS_BLOOM)?,
+        "prev_randao": decoder.hex(32)?,
+        "block_number": decoder.u64_string()?,
+        "gas_limit": decoder.u64_string()?,
+        "gas_used": decoder.u64_string()?,
+        "timestamp": decoder.u64_string()?,
+    });
+
+    let extra_data_offset = decoder.offset()?;
+
+    header["base_fee_per_gas"] = Value::String(U256::from_le_slice(decoder.take(32)?).to_string());
+    header["block_hash"] = decoder.hex(32)?;
+    header["transactions_root"] = decoder.hex(32)?;
+    header["withdrawals_root"] = decoder.hex(32)?;
+
+    if fork >= Fork::Deneb {
+        header["blob_gas_used"] = decoder.u64_string()?;
+        header["excess_blob_gas"] = decoder.u64_string()?;
+    }
+
+    decoder.ensure_fixed_end(extra_data_offset)?;
+
+    header["extra_data"] = Value::String(hex(decoder.variable(extra_data_offset, bytes.len())?));
+
+    Ok(header)
+}
+
+pub fn light_client_header(bytes: &[u8], fork: Fork) -> anyhow::Result<Value> {
+    let mut decoder = Decoder::new(bytes);
+
+    let beacon = beacon_block_header(decoder.take(BEACON_BLOCK_HEADER_LEN)?)?;
+    let execution_offset = decoder.offset()?;
+    let execution_branch = decoder.branch(EXECUTION_BRANCH_LEN)?;
+
+    decoder.ensure_fixed_end(execution_offset)?;
+
+    Ok(json!({
+        "beacon": beacon,
+        "execution": execution_payload_header(
+            decoder.variable(execution_offset, bytes.len())?,
+            fork,
+        )?,
+        "execution_branch": execution_branch,
+    }))
+}
+
+fn sync_committee(decoder: &mut Decoder, sync_committee_size: usize) -> anyhow::Result<Value> {
+    let pubkeys = (0..sync_committee_size)
+        .map(|_| decoder.hex(BYTES_PER_PUBKEY))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(json!({
+        "pubkeys": pubkeys,
+        "aggregate_pubkey": decoder.hex(BYTES_PER_PUBKEY)?,
+    }))
+}
+
+fn sync_aggregate(decoder: &mut Decoder, sync_committee_size: usize) -> anyhow::Result<Value> {
+    Ok(json!({
+        "sync_committee_bits": decoder.hex(sync_committee_size.div_ceil(8))?,
+        "sync_committee_signature": decoder.hex(BYTES_PER_SIGNATURE)?,
+    }))
+}
+
+pub fn light_client_update(
+    bytes: &[u8],
+    fork: Fork,
+    sync_committee_size: usize,
+) -> anyhow::Result<Value> {
+    let mut decoder = Decoder::new(bytes);
+
+    let attested_header_offset = decoder.offset()?;
+    let next_sync_committee = sync_committee(&mut decoder, sync_committee_size)?;
+    let next_sync_committee_branch = decoder.branch(fork.sync_committee_branch_len())?;
+    let finalized_header_offset = decoder.offset()?;
+    let finality_branch = decoder.branch(fork.finality_branch_len())?;
+    let sync_aggregate = sync_aggregate(&mut decoder, sync_committee_size)?;
+    let signature_slot = decoder.u64_string()?;
+
+    decoder.ensure_fixed_end(attested_header_offset)?;
+
+    Ok(json!({
+        "attested_header": light_client_header(
+            decoder.variable(attested_header_offset, finalized_header_offset)?,
+            fork,
+        )?,
+        "next_sync_committee": next_sync_committee,
+        "next_sync_committee_branch": next_sync_committee_branch,
+        "finalized_header": light_client_header(
+            decoder.variable(finalized_header_offset, bytes.len())?,
+            fork,
+        )?,
+        "finality_branch": finality_branch,
+        "sync_aggregate": sync_aggregate,
+        "signature_slot": signature_slot,
+    }))
+}
+
+pub fn light_client_bootstrap(
+    bytes: &[u8],
+    fork: Fork,
+    sync_committee_size: usize,
+) -> anyhow::Result<Value> {
+    let mut decoder = Decoder::new(bytes);
+
+    let header_offset = decoder.offset()?;
+    let current_sync_committee = sync_committee(&mut decoder, sync_committee_size)?;
+    let current_sync_committee_branch = decoder.branch(fork.sync_committee_branch_len())?;
+
+    decoder.ensure_fixed_end(header_offset)?;
+
+    Ok(json!({
+        "header": light_client_header(decoder.variable(header_offset, bytes.len())?, fork)?,
+        "current_sync_committee": current_sync_committee,
+        "current_sync_committee_branch": current_sync_committee_branch,
+    }))
+}
+
+pub fn light_client_finality_update(
+    bytes: &[u8],
+    fork: Fork,
+    sync_committee_size: usize,
+) -> anyhow::Result<Value> {
+    let mut decoder = Decoder::new(bytes);
+
+    let attested_header_offset = decoder.offset()?;
+    let finalized_header_offset = decoder.offset()?;
+    let finality_branch = decoder.branch(fork.finality_branch_len())?;
+    let sync_aggregate = sync_aggregate(&mut decoder, sync_committee_size)?;
+    let signature_slot = decoder.u64_string()?;
+
+    decoder.ensure_fixed_end(attested_header_offset)?;
+
+    Ok(json!({
+        "attested_header": light_client_header(
+            decoder.variable(attested_header_offset, finalized_header_offset)?,
+            fork,
+        )?,
+        "finalized_header": light_client_header(
+            decoder.variable(finalized_header_offset, bytes.len())?,
+            fork,
+        )?,
+        "finality_branch": finality_branch,
+        "sync_aggregate": sync_aggregate,
+        "signature_slot": signature_slot,
+    }))
+}
+
+pub fn light_client_optimistic_update(
+    bytes: &[u8],
+    fork: Fork,
+    sync_committee_size: usize,
+) -> anyhow::Result<Value> {
+    let mut decoder = Decoder::new(bytes);
+
+    let attested_header_offset = decoder.offset()?;
+    let sync_aggregate = sync_aggregate(&mut decoder, sync_committee_size)?;
+    let signature_slot = decoder.u64_string()?;
+
+    decoder.ensure_fixed_end(attested_header_offset)?;
+
+    Ok(json!({
+        "attested_header": light_client_header(
+            decoder.variable(attested_header_offset, bytes.len())?,
+            fork,
+        )?,
+        "sync_aggregate": sync_aggregate,
+        "signature_slot": signature_slot,
+    }))
+}
+
+// `light_client/updates` answers with `response_chunk_len || fork_digest || ssz_payload` chunks,
+// where the length covers the 4 byte digest as the beacon api spec defines it
+pub fn light_client_update_chunks(bytes: &[u8]) -> anyhow::Result<Vec<(ForkDigest, &[u8])>> {
+    let mut chunks = vec![];
+    let mut rest = bytes;
+
+    while !rest.is_empty() {
+        let len = u64::from_le_bytes(
+            rest.get(..8)
+                .context("truncated chunk length")?
+                .try_into()?,
+        );
+        let payload_len = usize::try_from(len)?
+            .checked_sub(4)
+            .with_context(|| format!("chunk length {} does not cover the fork digest", len))?;
+        let digest = rest
+            .get(8..12)
+            .context("truncated fork digest")?
+            .try_into()?;
+        // the length comes from the beacon node, so it can't be trusted not to overflow
+        let end = 12usize
+            .checked_add(payload_len)
+            .with_context(|| format!("chunk length {} overflows", len))?;
+        let payload = rest.get(12..end).with_context(|| {
+            format!(
+                "chunk of {} bytes but only {} left",
+                payload_len,
+                rest.len() - 12
+            )
+        })?;
+
+        chunks.push((digest, payload));
+        rest = &rest[end..];
+    }
+
+    Ok(chunks)
+}
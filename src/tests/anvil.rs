@@ -0,0 +1,167 @@
+use alloy::primitives::{address, Address, Bytes, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use testresult::TestResult;
+
+use crate::tests::logging;
+use crate::tests::network::anvil::AnvilPoA;
+use crate::tests::network::{unique_name, ChainControl, EthereumNetwork, Readiness};
+use crate::tests::scenario::relayer::deploy_ibc_handler;
+
+#[tokio::test]
+async fn test_anvil_snapshot_revert() -> TestResult {
+    let _guard = logging::init()?;
+
+    let mut network = AnvilPoA::default();
+    network.start().await?;
+
+    let result = async {
+        network.wait_ready(Readiness::ElUp).await?;
+
+        let config = network.network_config();
+        let provider = config.el_endpoint.provider().await?;
+
+        let shared = deploy_ibc_handler(&config.el_endpoint, &config.mnemonics[0]).await?;
+
+        let snapshot = network.snapshot().await?;
+
+        let scoped = deploy_ibc_handler(&config.el_endpoint, &config.mnemonics[0]).await?;
+        assert!(!provider.get_code_at(scoped).await?.is_empty());
+
+        network.revert(snapshot).await?;
+
+        assert!(provider.get_code_at(scoped).await?.is_empty());
+        assert!(!provider.get_code_at(shared).await?.is_empty());
+
+        // a snapshot can only be reverted once
+        assert!(network.revert(snapshot).await.is_err());
+
+        TestResult::Ok(())
+    }
+    .await;
+
+    network.stop().await?;
+    result
+}
+
+#[tokio::test]
+async fn test_anvil_state_roundtrip() -> TestResult {
+    let _guard = logging::init()?;
+
+    let state = std::env::temp_dir().join(format!("{}.json", unique_name("anvil-state")));
+
+    let mut network = AnvilPoA::builder().dump_state(state.clone()).build();
+    network.start().await?;
+    network.wait_ready(Readiness::ElUp).await?;
+
+    let config = network.network_config();
+    let deployed = deploy_ibc_handler(&config.el_endpoint, &config.mnemonics[0]).await?;
+
+    network.stop().await?;
+
+    let mut network = AnvilPoA::builder().load_state(state.clone()).build();
+    network.start().await?;
+
+    let result = async {
+        network.wait_ready(Readiness::ElUp).await?;
+
+        let provider = network.network_config().el_endpoint.provider().await?;
+        assert!(!provider.get_code_at(deployed).await?.is_empty());
+
+        TestResult::Ok(())
+    }
+    .await;
+
+    network.stop().await?;
+    std::fs::remove_file(state)?;
+    result
+}
+
+#[tokio::test]
+async fn test_anvil_chain_control() -> TestResult {
+    let _guard = logging::init()?;
+
+    // automine, blocks only move with transactions or when mined
+    let mut network = AnvilPoA::builder().block_time(0).build();
+    network.start().await?;
+
+    let result = async {
+        network.wait_ready(Readiness::ElUp).await?;
+
+        let provider = network.network_config().el_endpoint.provider().await?;
+
+        let block = provider.get_block_number().await?;
+        network.mine(5).await?;
+        assert_eq!(provider.get_block_number().await?, block + 5);
+
+        let timestamp = 4_000_000_000;
+        network.set_next_block_timestamp(timestamp).await?;
+        network.mine(1).await?;
+        let latest = provider
+            .get_block_by_number(Default::default(), Default::default())
+            .await?
+            .ok_or("no latest block")?;
+        assert_eq!(latest.header.timestamp, timestamp);
+
+        network.increase_time(3600).await?;
+        network.mine(1).await?;
+        let latest = provider
+            .get_block_by_number(Default::default(), Default::default())
+            .await?
+            .ok_or("no latest block")?;
+        assert!(latest.header.timestamp >= timestamp + 3600);
+
+        let whale = address!("00000000000000000000000000000000000000aa");
+        let receiver = address!("00000000000000000000000000000000000000bb");
+
+        network
+            .set_balance(whale, U256::from(10).pow(U256::from(18)))
+            .await?;
+        assert_eq!(
+            provider.get_balance(whale).await?,
+            U256::from(10).pow(U256::from(18))
+        );
+
+        network.impersonate(whale).await?;
+        let receipt = provider
+            .send_transaction(
+                TransactionRequest::default()
+                    .from(whale)
+                    .to(receiver)
+                    .value(U256::from(1)),
+            )
+            .await?;
+        network.mine(1).await?;
+        assert!(receipt.get_receipt().await?.status());
+        assert_eq!(provider.get_balance(receiver).await?, U256::from(1));
+        network.stop_impersonating(whale).await?;
+
+        let contract = Address::repeat_byte(0xcc);
+
+        // PUSH1 0 SLOAD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+        let code = Bytes::from_static(&[
+            0x60, 0x00, 0x54, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3,
+        ]);
+        network.set_code(contract, code.clone()).await?;
+        assert_eq!(provider.get_code_at(contract).await?, code);
+
+        let value = B256::repeat_byte(0x11);
+        network.set_storage_at(contract, U256::ZERO, value).await?;
+        assert_eq!(
+            provider.get_storage_at(contract, U256::ZERO).await?,
+            U256::from_be_bytes(value.0)
+        );
+        assert_eq!(
+            provider
+                .call(&TransactionRequest::default().to(contract))
+                .await?,
+            Bytes::from(value.to_vec())
+        );
+
+        TestResult::Ok(())
+    }
+    .await;
+
+    network.stop().await?;
+    result
+}
@@ -0,0 +1,77 @@
+use core::time::Duration;
+
+use testresult::TestResult;
+
+use crate::tests::network::compose::{parse_duration, ComposeFile, ComposeNetwork};
+
+#[test]
+fn test_compose_file() -> TestResult {
+    let network = ComposeNetwork::default();
+    let compose = ComposeFile::from_file(&network.compose_file)?;
+
+    assert_eq!(
+        compose.start_order()?,
+        vec!["reth", "lighthouse", "validator"]
+    );
+
+    let lighthouse = &compose.services["lighthouse"];
+    let cmd = lighthouse.cmd()?.ok_or("missing command")?;
+
+    // `--boot-nodes ""` has to stay an empty argument
+    let boot_nodes = cmd
+        .iter()
+        .position(|x| x == "--boot-nodes")
+        .ok_or("missing --boot-nodes")?;
+    assert_eq!(cmd[boot_nodes + 1], "");
+
+    assert_eq!(lighthouse.port_bindings()?, vec![(None, network.cl_port)]);
+    assert_eq!(
+        compose.services["reth"].port_bindings()?,
+        vec![(None, network.el_port)]
+    );
+
+    let health = lighthouse
+        .healthcheck
+        .as_ref()
+        .ok_or("missing healthcheck")?
+        .health_config()?;
+    assert_eq!(health.interval, Some(30_000_000_000));
+    assert_eq!(health.retries, Some(5));
+
+    assert_eq!(
+        network.container_name("reth"),
+        format!("{}-reth", network.project)
+    );
+    assert_ne!(network.project, ComposeNetwork::default().project);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_duration() -> TestResult {
+    assert_eq!(parse_duration("30s")?, Duration::from_secs(30));
+    assert_eq!(parse_duration("1m30s")?, Duration::from_secs(90));
+    assert_eq!(parse_duration("500ms")?, Duration::from_millis(500));
+    assert_eq!(parse_duration("1h")?, Duration::from_secs(3600));
+
+    assert!(parse_duration("30").is_err());
+    assert!(parse_duration("30d").is_err());
+    assert!(parse_duration("").is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_compose_empty_genesis() -> TestResult {
+    let mut network = ComposeNetwork::default();
+
+    let err = network
+        .start()
+        .await
+        .err()
+        .ok_or("started without a genesis")?;
+    assert!(format!("{:?}", err).contains("genesis.ssz"));
+    assert_eq!(network.el_socket, None);
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use k256::ecdsa::signature::Verifier;
+use k256::ecdsa::{Signature, VerifyingKey};
+use prost::Message;
+use protos::cosmos::tx::v1beta1::{AuthInfo, SignDoc, TxBody, TxRaw};
+use protos::ibc::core::client::v1::MsgUpdateClient;
+use testresult::TestResult;
+
+use crate::cosmos::{to_any, CosmosSigner, TxContext, MSG_UPDATE_CLIENT_TYPE_URL};
+
+const MNEMONIC: &str =
+    "abstract vacuum mammal awkward pudding scene penalty purchase dinner depart evoke puzzle";
+
+#[test]
+fn test_sign_tx_offline() -> TestResult {
+    let signer = CosmosSigner::from_mnemonic(MNEMONIC, "union")?;
+
+    let address = signer.address()?;
+    assert!(address.starts_with("union1"));
+
+    let msg = to_any(
+        MSG_UPDATE_CLIENT_TYPE_URL,
+        &MsgUpdateClient {
+            client_id: "08-wasm-0".to_string(),
+            client_message: None,
+            signer: address,
+        },
+    );
+
+    let context = TxContext::builder()
+        .chain_id("union-devnet-1")
+        .account_number(7)
+        .sequence(3)
+        .build();
+
+    let tx_bytes = signer.sign_tx(vec![msg.clone()], &context)?;
+
+    let tx_raw = TxRaw::decode(tx_bytes.as_slice())?;
+
+    let body = TxBody::decode(tx_raw.body_bytes.as_slice())?;
+    assert_eq!(body.messages, vec![msg]);
+
+    let auth_info = AuthInfo::decode(tx_raw.auth_info_bytes.as_slice())?;
+    assert_eq!(auth_info.signer_infos.len(), 1);
+    assert_eq!(auth_info.signer_infos[0].sequence, 3);
+
+    let sign_doc = SignDoc {
+        body_bytes: tx_raw.body_bytes,
+        auth_info_bytes: tx_raw.auth_info_bytes,
+        chain_id: "union-devnet-1".to_string(),
+        account_number: 7,
+    };
+
+    let signature = Signature::from_slice(&tx_raw.signatures[0])?;
+    assert!(signature.normalize_s().is_none(), "signature must be low-s");
+
+    VerifyingKey::from_sec1_bytes(&signer.public_key())?
+        .verify(&sign_doc.encode_to_vec(), &signature)?;
+
+    Ok(())
+}
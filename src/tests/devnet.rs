@@ -0,0 +1,113 @@
+use std::ffi::OsStr;
+
+use alloy_signer_local::coins_bip39::English;
+use alloy_signer_local::MnemonicBuilder;
+use testresult::TestResult;
+
+use crate::tests::network::devnet::{DevnetPorts, LocalDevnet, PROCESSES};
+use crate::tests::network::{unique_name, EthereumNetwork};
+
+#[test]
+fn test_devnet_commands() -> TestResult {
+    let ports = DevnetPorts::free()?;
+
+    let network = LocalDevnet::builder().ports(ports).build();
+
+    for file in [
+        "genesis.json",
+        "jwt.hex",
+        "testnet/config.yaml",
+        "testnet/genesis.ssz",
+    ] {
+        assert!(network.data_dir.join(file).is_file(), "missing {}", file);
+    }
+
+    let args = |command: std::process::Command| {
+        command
+            .get_args()
+            .map(OsStr::to_string_lossy)
+            .map(|x| x.into_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let reth = args(network.reth_command());
+    assert!(reth.contains(&ports.el_http.to_string()));
+    assert!(reth.contains(&ports.el_authrpc.to_string()));
+
+    // the beacon node drives reth over the engine api with the shared secret
+    let beacon = args(network.beacon_node_command());
+    assert!(beacon.contains(&format!("http://127.0.0.1:{}", ports.el_authrpc)));
+    assert!(beacon.contains(&network.data_dir.join("jwt.hex").display().to_string()));
+    assert!(beacon.contains(&ports.cl_http.to_string()));
+
+    let validator = args(network.validator_command());
+    assert!(validator.contains(&format!("http://127.0.0.1:{}", ports.cl_http)));
+
+    // scenarios pay from the first account of the first mnemonic
+    let genesis: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(network.data_dir.join("genesis.json"))?)?;
+    let wallet = MnemonicBuilder::<English>::default()
+        .phrase(network.network_config().mnemonics[0].as_str())
+        .build()?;
+    let address = format!("{:#x}", wallet.address());
+    assert!(genesis["alloc"][address.as_str()]["balance"].is_string());
+
+    assert!(network.work_dir.starts_with(std::env::temp_dir()));
+    assert_ne!(network.work_dir, LocalDevnet::default().work_dir);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_devnet_empty_genesis() -> TestResult {
+    let mut network = LocalDevnet::default();
+
+    let err = network
+        .start()
+        .await
+        .err()
+        .ok_or("started without a genesis")?;
+    assert!(format!("{:?}", err).contains("genesis.ssz"));
+    assert!(!network.work_dir.exists());
+
+    network.stop().await?;
+
+    Ok(())
+}
+
+// reth exits right away when sh runs it, the lighthouse commands succeed as true
+#[tokio::test]
+async fn test_devnet_failed_start() -> TestResult {
+    let root = std::env::temp_dir().join(unique_name("devnet-failed"));
+    let data_dir = root.join("data");
+    std::fs::create_dir_all(data_dir.join("testnet"))?;
+    std::fs::write(data_dir.join("testnet/genesis.ssz"), [0])?;
+
+    let mut network = LocalDevnet::builder()
+        .reth_bin("sh".into())
+        .lighthouse_bin("true".into())
+        .data_dir(data_dir)
+        .work_dir(root.join("work"))
+        .build();
+
+    assert!(network.start().await.is_err());
+
+    let artifacts_dir = root.join("artifacts");
+    network.on_failure(&artifacts_dir).await?;
+
+    for name in PROCESSES {
+        assert!(
+            artifacts_dir.join(format!("{}.log", name)).is_file(),
+            "missing {}",
+            name
+        );
+    }
+
+    let work_dir = network.work_dir.clone();
+    network.stop().await?;
+    assert!(work_dir.join("reth.log").is_file());
+
+    std::fs::remove_dir_all(root)?;
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use alloy::primitives::U64;
+use alloy::rpc::client::ClientBuilder;
+use axum::routing::post;
+use axum::Json;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, AUTHORIZATION};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use testresult::TestResult;
+
+use crate::endpoint::{Auth, Endpoint, HeaderHttp, Scheme};
+
+// seconds the clock of test_jwt_per_request is ahead
+static CLOCK_OFFSET: AtomicU64 = AtomicU64::new(0);
+
+fn offset_clock() -> SystemTime {
+    SystemTime::now() + Duration::from_secs(CLOCK_OFFSET.load(Ordering::Relaxed))
+}
+
+fn jwt_iat(authorization: &str) -> TestResult<u64> {
+    let token = authorization
+        .strip_prefix("Bearer ")
+        .ok_or("not a bearer token")?;
+    let claims = token.split('.').nth(1).ok_or("no claims")?;
+    let claims = serde_json::from_slice::<Value>(&URL_SAFE_NO_PAD.decode(claims)?)?;
+
+    Ok(claims["iat"].as_u64().ok_or("no iat")?)
+}
+
+#[test]
+fn test_parse() -> TestResult {
+    let endpoint = "127.0.0.1:8545".parse::<Endpoint>()?;
+    assert_eq!(endpoint.scheme()?, Scheme::Http);
+    assert_eq!(endpoint.to_string(), "http://127.0.0.1:8545");
+
+    let endpoint = "wss://node.example/rpc".parse::<Endpoint>()?;
+    assert_eq!(endpoint.scheme()?, Scheme::Ws);
+
+    let endpoint = "/tmp/reth.ipc".parse::<Endpoint>()?;
+    assert_eq!(endpoint.scheme()?, Scheme::Ipc);
+    assert_eq!(endpoint.url.path(), "/tmp/reth.ipc");
+
+    assert!("ftp://node.example".parse::<Endpoint>()?.scheme().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_join_keeps_prefix() -> TestResult {
+    let endpoint = "https://node.example/beacon/".parse::<Endpoint>()?;
+
+    assert_eq!(
+        endpoint.join("/eth/v1/node/health")?.as_str(),
+        "https://node.example/beacon/eth/v1/node/health"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_header_map() -> TestResult {
+    let endpoint = Endpoint::builder()
+        .url("https://node.example".parse()?)
+        .auth(Auth::Bearer("token".into()))
+        .headers(vec![("x-api-key".into(), "key".into())])
+        .build();
+
+    let headers = endpoint.header_map()?;
+
+    assert_eq!(headers[AUTHORIZATION], "Bearer token");
+    assert_eq!(headers["x-api-key"], "key");
+
+    Ok(())
+}
+
+#[test]
+fn test_jwt() -> TestResult {
+    let secret = [7u8; 32];
+
+    let auth = Auth::jwt_from_hex(&format!("0x{}", hex::encode(secret)))?;
+    assert_eq!(auth, Auth::Jwt(secret));
+
+    let token = auth.token()?;
+    let (message, signature) = token.rsplit_once('.').ok_or("no signature")?;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(&secret)?;
+    mac.update(message.as_bytes());
+    mac.verify_slice(&URL_SAFE_NO_PAD.decode(signature)?)?;
+
+    let claims = message.split_once('.').ok_or("no claims")?.1;
+    let claims = serde_json::from_slice::<serde_json::Value>(&URL_SAFE_NO_PAD.decode(claims)?)?;
+    assert!(claims["iat"].as_u64().is_some());
+
+    assert!(Auth::jwt_from_hex("abcd").is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_jwt_per_request() -> TestResult {
+    let authorizations = Arc::new(Mutex::new(Vec::<String>::new()));
+
+    let router = axum::Router::new().route(
+        "/",
+        post({
+            let authorizations = authorizations.clone();
+
+            move |headers: HeaderMap, Json(request): Json<Value>| async move {
+                authorizations.lock().expect("poisoned").push(
+                    headers
+                        .get(AUTHORIZATION)
+                        .and_then(|x| x.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string(),
+                );
+
+                Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": "0x539" }))
+            }
+        }),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}", listener.local_addr()?).parse()?;
+
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let client = ClientBuilder::default()
+        .transport(
+            HeaderHttp::builder()
+                .endpoint(
+                    Endpoint::builder()
+                        .url(url)
+                        .auth(Auth::Jwt([7; 32]))
+                        .build(),
+                )
+                .clock(offset_clock)
+                .build(),
+            true,
+        )
+        .boxed();
+
+    client.request::<_, U64>("eth_chainId", ()).await?;
+
+    // past the engine api's 60s window, a token signed once per client would be rejected
+    CLOCK_OFFSET.store(61, Ordering::Relaxed);
+
+    assert_eq!(
+        client.request::<_, U64>("eth_chainId", ()).await?,
+        U64::from(1337)
+    );
+
+    let authorizations = authorizations.lock().expect("poisoned").clone();
+    assert_eq!(authorizations.len(), 2);
+
+    let (first, second) = (jwt_iat(&authorizations[0])?, jwt_iat(&authorizations[1])?);
+    assert!(second >= first + 61, "iat {} then {}", first, second);
+
+    Ok(())
+}
@@ -0,0 +1,262 @@
+use std::io::Read;
+
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use testresult::TestResult;
+
+use crate::tests::network::ethpkg::config::{
+    AdditionalService, ClType, ElType, EthPkgConfig, NetworkParams, Participant, Preset,
+    DEFAULT_MNEMONIC, EL_PROOF_WINDOW,
+};
+use crate::tests::network::ethpkg::{EthPkgKurtosis, Package, PACKAGE_ID, UPLOAD_CHUNK_SIZE};
+use crate::tests::network::unique_name;
+
+#[test]
+fn test_default_params() -> TestResult {
+    assert_eq!(
+        serde_json::to_value(EthPkgConfig::default())?,
+        json!({
+            "participants": [{
+                "cl_type": "lodestar",
+                "el_type": "reth",
+                "el_extra_params": [format!("--rpc.eth-proof-window={}", EL_PROOF_WINDOW)],
+                "count": 1,
+            }],
+            "network_params": {
+                "network": "kurtosis",
+                "preset": "minimal",
+                "seconds_per_slot": 1,
+                "preregistered_validator_keys_mnemonic": DEFAULT_MNEMONIC,
+            },
+            "wait_for_finalization": true,
+        })
+    );
+
+    assert_eq!(
+        EthPkgConfig::default().el_proof_window(),
+        Some(EL_PROOF_WINDOW)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_client_mix() -> TestResult {
+    let config = EthPkgConfig::builder()
+        .participants(vec![
+            Participant::default(),
+            Participant::builder()
+                .cl_type(ClType::Teku)
+                .el_type(ElType::Geth)
+                .el_image("ethereum/client-go:latest".into())
+                .count(2)
+                .build(),
+        ])
+        .network_params(
+            NetworkParams::builder()
+                .preset(Preset::Mainnet)
+                .electra_fork_epoch(1)
+                .num_validator_keys_per_node(128)
+                .build(),
+        )
+        .additional_services(vec![AdditionalService::PrometheusGrafana])
+        .build();
+
+    let params = serde_json::to_value(&config)?;
+
+    assert_eq!(params["participants"][1]["el_type"], "geth");
+    assert_eq!(params["participants"][1]["count"], 2);
+    assert_eq!(params["network_params"]["preset"], "mainnet");
+    assert_eq!(params["network_params"]["electra_fork_epoch"], 1);
+    assert_eq!(params["additional_services"], json!(["prometheus_grafana"]));
+
+    // geth doesn't take the reth proof window flag
+    assert_eq!(config.el_proof_window(), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_partial_yaml() -> TestResult {
+    let config = serde_yaml::from_str::<EthPkgConfig>(
+        "participants:\n  - cl_type: prysm\n    el_type: nethermind\nnetwork_params:\n  seconds_per_slot: 2\n",
+    )?;
+
+    assert_eq!(config.participants[0].cl_type, ClType::Prysm);
+    assert_eq!(config.participants[0].count, 1);
+    assert_eq!(config.network_params.seconds_per_slot, 2);
+    assert_eq!(config.network_params.preset, Preset::Minimal);
+    assert!(config.wait_for_finalization);
+
+    Ok(())
+}
+
+#[test]
+fn test_config_hash() -> TestResult {
+    let hash = EthPkgConfig::default().hash()?;
+
+    assert_eq!(hash.len(), 12);
+    assert_eq!(hash, EthPkgConfig::default().hash()?);
+
+    let config = EthPkgConfig::builder()
+        .network_params(NetworkParams::builder().seconds_per_slot(2).build())
+        .build();
+
+    assert_ne!(hash, config.hash()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_reused_enclave_name() -> TestResult {
+    let network = |config: EthPkgConfig, reuse_enclave: bool| {
+        EthPkgKurtosis::builder()
+            .enclave_name("relayer".into())
+            .config(config)
+            .reuse_enclave(reuse_enclave)
+            .build()
+    };
+
+    let other = || {
+        EthPkgConfig::builder()
+            .network_params(NetworkParams::builder().seconds_per_slot(2).build())
+            .build()
+    };
+
+    // a given name is kept as is without reuse
+    assert_eq!(
+        network(EthPkgConfig::default(), false).resolve_name()?,
+        "relayer"
+    );
+
+    // but never shared between configs when reusing
+    let name = network(EthPkgConfig::default(), true).resolve_name()?;
+    assert_eq!(name, format!("relayer-{}", EthPkgConfig::default().hash()?));
+    assert_eq!(name, network(EthPkgConfig::default(), true).resolve_name()?);
+    assert_ne!(name, network(other(), true).resolve_name()?);
+
+    assert!(EthPkgKurtosis::builder()
+        .enclave_name("x".repeat(60))
+        .reuse_enclave(true)
+        .build()
+        .resolve_name()
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_pin_images() -> TestResult {
+    let config = EthPkgConfig::builder()
+        .participants(vec![
+            Participant::default(),
+            Participant::builder()
+                .cl_type(ClType::Teku)
+                .el_type(ElType::Geth)
+                .el_image("ethereum/client-go:latest".into())
+                .build(),
+        ])
+        .build()
+        .pin_images()?;
+
+    assert_eq!(
+        config.participants[0].cl_image.as_deref(),
+        Some(ClType::Lodestar.image())
+    );
+    assert_eq!(
+        config.participants[0].el_image.as_deref(),
+        ElType::Reth.image()
+    );
+    assert_eq!(
+        config.participants[1].cl_image.as_deref(),
+        Some(ClType::Teku.image())
+    );
+    assert_eq!(
+        config.participants[1].el_image.as_deref(),
+        Some("ethereum/client-go:latest")
+    );
+
+    for participant in &config.participants {
+        assert!(!participant
+            .cl_image
+            .as_deref()
+            .unwrap_or_default()
+            .ends_with(":latest"));
+    }
+
+    // ethereumjs has no release tag to pin to
+    assert!(Participant::builder()
+        .el_type(ElType::Ethereumjs)
+        .build()
+        .pin_images()
+        .is_err());
+    assert_eq!(
+        Participant::builder()
+            .el_type(ElType::Ethereumjs)
+            .el_image("ethpandaops/ethereumjs:local".into())
+            .build()
+            .pin_images()?
+            .el_image
+            .as_deref(),
+        Some("ethpandaops/ethereumjs:local")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_local_package() -> TestResult {
+    let root = std::env::temp_dir().join(unique_name("ethpkg-local"));
+    let dir = root.join("ethereum-package");
+    std::fs::create_dir_all(dir.join("src"))?;
+    std::fs::write(dir.join("kurtosis.yml"), format!("name: {}\n", PACKAGE_ID))?;
+    std::fs::write(dir.join("main.star"), "def run(plan, args={}):\n    pass\n")?;
+
+    let package = Package::Dir(dir);
+    assert!(!package.is_remote());
+    assert_eq!(package.id(), PACKAGE_ID);
+
+    let content = package.content()?.ok_or("missing package content")?;
+
+    let mut tarball = vec![];
+    flate2::read::GzDecoder::new(&content[..]).read_to_end(&mut tarball)?;
+
+    let paths = tar::Archive::new(&tarball[..])
+        .entries()?
+        .map(|entry| Ok(entry?.path()?.display().to_string()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+
+    assert!(paths.contains(&"ethereum-package/kurtosis.yml".to_string()));
+    assert!(paths.contains(&"ethereum-package/main.star".to_string()));
+
+    assert_eq!(Package::default().id(), PACKAGE_ID);
+    assert_eq!(Package::default().content()?, None);
+    assert!(Package::default().upload_chunks()?.is_empty());
+
+    let chunks = package.upload_chunks()?;
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].previous_chunk_hash, "");
+    assert_eq!(
+        chunks[0]
+            .metadata
+            .as_ref()
+            .map(|metadata| &metadata.name[..]),
+        Some(PACKAGE_ID)
+    );
+
+    // larger packages are chained by the sha1 of the previous chunk
+    let archive = root.join("pkg.tgz");
+    std::fs::write(&archive, vec![7; UPLOAD_CHUNK_SIZE + 1])?;
+
+    let chunks = Package::Archive(archive).upload_chunks()?;
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[1].data, [7]);
+    assert_eq!(
+        chunks[1].previous_chunk_hash,
+        hex::encode(Sha1::digest(&chunks[0].data))
+    );
+
+    std::fs::remove_dir_all(root)?;
+
+    Ok(())
+}
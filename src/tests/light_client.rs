@@ -0,0 +1,240 @@
+use anyhow::Context;
+use blst::min_pk::SecretKey;
+use serde_json::Value;
+use testresult::TestResult;
+use unionlabs::ibc::lightclients::ethereum::light_client_update::UnboundedLightClientUpdate;
+
+use crate::cosmos::wasm::BLS_DST;
+use crate::light_client::{
+    is_sync_committee_update, BlsVerifier, Blst, ForkSchedule, LightClientConfig, LightClientStore,
+    SyncAggregateBits, UpdateRank, DOMAIN_SYNC_COMMITTEE,
+};
+use crate::relayer::ssz::Fork;
+use crate::tests::relayer::update;
+
+const SLOTS_PER_PERIOD: u64 = 64;
+
+fn config() -> LightClientConfig {
+    LightClientConfig::builder()
+        .genesis_validators_root([1; 32])
+        .genesis_time(1_000)
+        .seconds_per_slot(6)
+        .slots_per_epoch(8)
+        .epochs_per_sync_committee_period(8)
+        .forks(vec![
+            ForkSchedule {
+                epoch: 0,
+                version: [0, 0, 0, 1],
+                fork: None,
+            },
+            ForkSchedule {
+                epoch: 0,
+                version: [1, 0, 0, 1],
+                fork: Some(Fork::Altair),
+            },
+            ForkSchedule {
+                epoch: 2,
+                version: [3, 0, 0, 1],
+                fork: Some(Fork::Capella),
+            },
+        ])
+        .build()
+}
+
+fn rank(active_participants: u64) -> UpdateRank {
+    UpdateRank {
+        active_participants,
+        has_supermajority: active_participants * 3 >= 32 * 2,
+        is_sync_committee_update: false,
+        is_finality_update: true,
+        attested_slot: 10,
+        finalized_slot: 8,
+        signature_slot: 11,
+    }
+}
+
+#[test]
+fn test_config_slots() {
+    let config = config();
+
+    assert_eq!(config.slots_per_period(), SLOTS_PER_PERIOD);
+    assert_eq!(config.period_at_slot(SLOTS_PER_PERIOD - 1), 0);
+    assert_eq!(config.period_at_slot(SLOTS_PER_PERIOD), 1);
+    assert_eq!(config.current_slot(1_000 + 6 * 5 + 1), 5);
+    assert_eq!(config.fork_at_slot(15).ok().flatten(), Some(Fork::Altair));
+    assert_eq!(config.fork_at_slot(16).ok().flatten(), Some(Fork::Capella));
+}
+
+#[test]
+fn test_compute_domain() {
+    let domain = config().compute_domain(DOMAIN_SYNC_COMMITTEE, [3, 0, 0, 1]);
+
+    assert_eq!(domain[..4], DOMAIN_SYNC_COMMITTEE);
+    assert_ne!(
+        domain,
+        config().compute_domain(DOMAIN_SYNC_COMMITTEE, [1, 0, 0, 1])
+    );
+}
+
+#[test]
+fn test_sync_aggregate_bits() {
+    let bits = SyncAggregateBits {
+        bits: vec![0b1000_0001, 0xff, 0xff, 0x0f],
+        size: 32,
+    };
+
+    assert!(bits.get(0));
+    assert!(!bits.get(1));
+    assert!(bits.get(7));
+    assert!(!bits.get(28));
+    assert_eq!(bits.active_participants(), 22);
+    assert!(bits.has_supermajority());
+}
+
+#[test]
+fn test_is_better_update() {
+    // supermajority wins over participation
+    assert!(rank(22).is_better_than(&rank(20), SLOTS_PER_PERIOD));
+    assert!(!rank(20).is_better_than(&rank(22), SLOTS_PER_PERIOD));
+
+    // relevant sync committee wins over finality
+    let sync_committee_update = UpdateRank {
+        is_sync_committee_update: true,
+        is_finality_update: false,
+        ..rank(22)
+    };
+    assert!(sync_committee_update.is_better_than(&rank(32), SLOTS_PER_PERIOD));
+
+    // a sync committee signed in the next period is not relevant
+    let irrelevant = UpdateRank {
+        signature_slot: SLOTS_PER_PERIOD,
+        ..sync_committee_update.clone()
+    };
+    assert!(!irrelevant.is_better_than(&rank(22), SLOTS_PER_PERIOD));
+
+    // older data wins the tie
+    let newer = UpdateRank {
+        attested_slot: 12,
+        signature_slot: 13,
+        ..rank(22)
+    };
+    assert!(rank(22).is_better_than(&newer, SLOTS_PER_PERIOD));
+    assert!(!newer.is_better_than(&rank(22), SLOTS_PER_PERIOD));
+    assert!(!rank(22).is_better_than(&rank(22), SLOTS_PER_PERIOD));
+}
+
+#[test]
+fn test_blst_fast_aggregate_verify() -> TestResult {
+    let secret_keys = (1..=4u8)
+        .map(|i| SecretKey::key_gen(&[i; 32], &[]))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("{:?}", err))?;
+
+    let message = [9; 32];
+
+    let signatures = secret_keys
+        .iter()
+        .map(|secret_key| secret_key.sign(&message, BLS_DST, &[]))
+        .collect::<Vec<_>>();
+
+    let signature =
+        blst::min_pk::AggregateSignature::aggregate(&signatures.iter().collect::<Vec<_>>(), true)
+            .map_err(|err| format!("{:?}", err))?
+            .to_signature()
+            .compress();
+
+    let public_keys = secret_keys
+        .iter()
+        .map(|secret_key| secret_key.sk_to_pk().compress())
+        .collect::<Vec<_>>();
+    let public_keys = public_keys.iter().map(|x| &x[..]).collect::<Vec<_>>();
+
+    assert!(Blst.fast_aggregate_verify(&public_keys, &message, &signature)?);
+    assert!(!Blst.fast_aggregate_verify(&public_keys[1..], &message, &signature)?);
+    assert!(!Blst.fast_aggregate_verify(&public_keys, &[8; 32], &signature)?);
+
+    Ok(())
+}
+
+// every hex string, at any depth, replaced with zeros of the same length
+fn zeroed(value: &Value) -> Value {
+    match value {
+        Value::String(x) if x.starts_with("0x") => {
+            Value::String(format!("0x{}", "0".repeat(x.len() - 2)))
+        }
+        Value::Array(x) => Value::Array(x.iter().map(zeroed).collect()),
+        Value::Object(x) => Value::Object(x.iter().map(|(k, v)| (k.clone(), zeroed(v))).collect()),
+        x => x.clone(),
+    }
+}
+
+// an altair update attested at slot 10, neither a finality nor a sync committee update
+fn non_sync_committee_update(
+    next_sync_committee: impl FnOnce(&Value) -> Value,
+) -> anyhow::Result<(LightClientStore, UnboundedLightClientUpdate)> {
+    let mut value = serde_json::to_value(update(8)?)?;
+
+    value["attested_header"]["beacon"]["slot"] = "10".into();
+    value["attested_header"]["execution_branch"] =
+        zeroed(&value["attested_header"]["execution_branch"]);
+    value["finalized_header"] = zeroed(&value["finalized_header"]);
+    value["finalized_header"]["beacon"]["slot"] = "0".into();
+    value["finalized_header"]["beacon"]["proposer_index"] = "0".into();
+    value["finality_branch"] = zeroed(&value["finality_branch"]);
+    value["next_sync_committee_branch"] = zeroed(&value["next_sync_committee_branch"]);
+    value["next_sync_committee"] = next_sync_committee(&value["next_sync_committee"]);
+    value["signature_slot"] = "11".into();
+
+    let update: UnboundedLightClientUpdate = serde_json::from_value(value)?;
+
+    let mut finalized_header = update.attested_header.clone();
+    finalized_header.beacon.slot = 8;
+
+    let store = LightClientStore {
+        config: config(),
+        finalized_header: finalized_header.clone(),
+        current_sync_committee: update
+            .next_sync_committee
+            .clone()
+            .context("next sync committee")?,
+        next_sync_committee: None,
+        best_valid_update: None,
+        optimistic_header: finalized_header,
+        previous_max_active_participants: 0,
+        current_max_active_participants: 0,
+        verifier: Blst,
+    };
+
+    Ok((store, update))
+}
+
+#[test]
+fn test_validate_non_sync_committee_update() -> TestResult {
+    let (store, update) = non_sync_committee_update(Value::clone)?;
+
+    assert!(!is_sync_committee_update(&update));
+
+    let err = store
+        .validate_light_client_update(&update, 100)
+        .expect_err("next sync committee without a branch");
+    assert!(
+        err.to_string()
+            .contains("without a next sync committee branch"),
+        "{:?}",
+        err
+    );
+
+    // an empty committee gets past the check, only to fail on the fake signature
+    let (store, update) = non_sync_committee_update(zeroed)?;
+
+    let err = store
+        .validate_light_client_update(&update, 100)
+        .expect_err("the signature is not valid");
+    assert!(
+        !err.to_string().contains("next sync committee"),
+        "{:?}",
+        err
+    );
+
+    Ok(())
+}
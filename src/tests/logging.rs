@@ -0,0 +1,61 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tracing::subscriber::DefaultGuard;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::EnvFilter;
+
+pub const TEST_LOG_DIR: &str = "TEST_LOG_DIR";
+pub const TEST_ARTIFACTS_DIR: &str = "TEST_ARTIFACTS_DIR";
+
+// libtest names each test thread after the test, which keeps the files apart
+pub fn test_name() -> String {
+    std::thread::current()
+        .name()
+        .unwrap_or("test")
+        .replace("::", "-")
+}
+
+fn dir_from_env(var: &str, default: &str) -> PathBuf {
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(default))
+}
+
+pub fn log_path() -> PathBuf {
+    dir_from_env(TEST_LOG_DIR, "target/test-logs").join(format!("{}.jsonl", test_name()))
+}
+
+// where a failing test leaves whatever its network can dump, like service logs
+pub fn artifacts_dir() -> PathBuf {
+    dir_from_env(TEST_ARTIFACTS_DIR, "target/test-artifacts").join(test_name())
+}
+
+// human readable logs on stdout and json lines in the test's log file, RUST_LOG filters both
+pub fn init() -> anyhow::Result<DefaultGuard> {
+    let path = log_path();
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let file = Arc::new(File::create(&path)?);
+
+    let subscriber = tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with(tracing_subscriber::fmt::layer().with_test_writer())
+        .with(
+            tracing_subscriber::fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_writer(file),
+        );
+
+    let guard = tracing::subscriber::set_default(subscriber);
+
+    tracing::info!(path = %path.display(), "writing json logs");
+
+    Ok(guard)
+}
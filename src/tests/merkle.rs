@@ -0,0 +1,50 @@
+use testresult::TestResult;
+
+use crate::merkle::{
+    beacon_block_header_root, hash_pair, is_valid_merkle_branch, merkleize, uint64_root, Root,
+};
+
+fn leaf(n: u8) -> Root {
+    [n; 32]
+}
+
+#[test]
+fn test_merkle_branch_roundtrip() {
+    let leaves = (0..8).map(leaf).collect::<Vec<_>>();
+
+    let root = merkleize(leaves.clone());
+
+    // branch for index 5: sibling 4, then hash(6, 7), then hash(hash(0, 1), hash(2, 3))
+    let branch = [
+        leaf(4),
+        hash_pair(&leaf(6), &leaf(7)),
+        hash_pair(
+            &hash_pair(&leaf(0), &leaf(1)),
+            &hash_pair(&leaf(2), &leaf(3)),
+        ),
+    ];
+
+    assert!(is_valid_merkle_branch(leaf(5), &branch, 5, root));
+    assert!(!is_valid_merkle_branch(leaf(5), &branch, 4, root));
+    assert!(!is_valid_merkle_branch(leaf(4), &branch, 5, root));
+}
+
+#[test]
+fn test_beacon_block_header_root_pads_to_eight_fields() -> TestResult {
+    let root = beacon_block_header_root(1, 2, leaf(3), leaf(4), leaf(5))?;
+
+    let expected = hash_pair(
+        &hash_pair(
+            &hash_pair(&uint64_root(1), &uint64_root(2)),
+            &hash_pair(&leaf(3), &leaf(4)),
+        ),
+        &hash_pair(
+            &hash_pair(&leaf(5), &[0; 32]),
+            &hash_pair(&[0; 32], &[0; 32]),
+        ),
+    );
+
+    assert_eq!(root, expected);
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use testresult::TestResult;
+
+use crate::relayer::metrics::{endpoint, error_kind, Metrics};
+use crate::relayer::OutsideProofWindow;
+
+#[test]
+fn test_endpoint_strips_ids() {
+    assert_eq!(
+        endpoint("/eth/v1/beacon/light_client/updates?start_period=3&count=2"),
+        "/eth/v1/beacon/light_client/updates"
+    );
+    assert_eq!(
+        endpoint("/eth/v1/beacon/light_client/bootstrap/0xabcd"),
+        "/eth/v1/beacon/light_client/bootstrap/:id"
+    );
+    assert_eq!(
+        endpoint("/eth/v2/beacon/blocks/12/root"),
+        "/eth/v2/beacon/blocks/:id/root"
+    );
+}
+
+#[test]
+fn test_error_kind() {
+    let err = anyhow::Error::from(OutsideProofWindow {
+        execution_height: 1,
+        latest_height: 200,
+        proof_window: 128,
+    });
+    assert_eq!(error_kind(&err), "outside_proof_window");
+
+    let err = anyhow::Error::from(serde_json::from_str::<u64>("nope").unwrap_err());
+    assert_eq!(error_kind(&err), "decode");
+
+    assert_eq!(error_kind(&anyhow::anyhow!("nope")), "other");
+}
+
+#[test]
+fn test_encode() -> TestResult {
+    let metrics = Metrics::new()?;
+
+    metrics.headers_built.inc_by(3);
+    metrics.trusted_slot_lag.set(40);
+    metrics.error("header", &anyhow::anyhow!("nope"));
+
+    let encoded = metrics.encode()?;
+
+    assert!(encoded.contains("relayer_headers_built_total 3"));
+    assert!(encoded.contains("relayer_trusted_slot_lag 40"));
+    assert!(encoded.contains("relayer_errors_total{call=\"header\",kind=\"other\"} 1"));
+
+    Ok(())
+}
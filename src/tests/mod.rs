@@ -1,45 +1,152 @@
+pub mod anvil;
+pub mod compose;
+pub mod cosmos;
+pub mod devnet;
+pub mod endpoint;
+pub mod ethpkg;
+pub mod light_client;
+pub mod logging;
+pub mod merkle;
+pub mod metrics;
 pub mod network;
+pub mod relayer;
 pub mod scenario;
+pub mod spec;
+pub mod ssz;
 
 use network::anvil::AnvilPoA;
+use network::ethpkg::config::{ClType, ElType, EthPkgConfig, NetworkParams, Participant, Preset};
 use network::ethpkg::EthPkgKurtosis;
 use network::EthereumNetwork as Network;
 use rstest::rstest;
 use scenario::beacon::BeaconEndpoint;
+use scenario::checkpoint::CheckpointInit;
+use scenario::daemon::RelayerDaemon;
 use scenario::erc20::ERC20Transfer;
+use scenario::lifecycle::ClientLifecycle;
+use scenario::optimistic::OptimisticTracking;
 use scenario::relayer::RelayerMsg;
+use scenario::rpc::RelayerRpc;
+use scenario::ssz::SszTransport;
+use scenario::store::LightClientSync;
+use scenario::wasm::WasmClientExec;
 use testresult::TestResult;
+use tracing::Instrument;
 
 use crate::tests::scenario::Scenario;
 
+pub const SCENARIO_TIMEOUT: &str = "SCENARIO_TIMEOUT";
+pub const DEFAULT_SCENARIO_TIMEOUT: u64 = 3600;
+
 #[rstest]
 #[case::anvil_erc20_transfer(AnvilPoA::default(), ERC20Transfer)]
 #[case::kurtosis_erc20_transfer(EthPkgKurtosis::default(), ERC20Transfer)]
+#[case::kurtosis_client_mix_erc20_transfer(
+    EthPkgKurtosis::builder()
+        .config(
+            EthPkgConfig::builder()
+                .participants(vec![
+                    Participant::default(),
+                    Participant::builder()
+                        .cl_type(ClType::Teku)
+                        .el_type(ElType::Geth)
+                        .build(),
+                ])
+                .build(),
+        )
+        .build(),
+    ERC20Transfer
+)]
+#[case::kurtosis_mainnet_erc20_transfer(
+    EthPkgKurtosis::builder()
+        .config(
+            EthPkgConfig::builder()
+                .network_params(NetworkParams::builder().preset(Preset::Mainnet).build())
+                .build(),
+        )
+        .build(),
+    ERC20Transfer
+)]
 #[case::kurtosis_finality_endpoint(EthPkgKurtosis::default(), BeaconEndpoint)]
 #[case::kurtosis_finality_protobuf(EthPkgKurtosis::default(), RelayerMsg)]
+#[case::kurtosis_client_lifecycle(EthPkgKurtosis::default(), ClientLifecycle)]
+#[case::kurtosis_wasm_light_client(EthPkgKurtosis::default(), WasmClientExec)]
+#[case::kurtosis_relayer_daemon(EthPkgKurtosis::default(), RelayerDaemon)]
+#[case::kurtosis_checkpoint_init(EthPkgKurtosis::default(), CheckpointInit)]
+#[case::kurtosis_ssz_transport(EthPkgKurtosis::default(), SszTransport)]
+#[case::kurtosis_optimistic_tracking(EthPkgKurtosis::default(), OptimisticTracking)]
+#[case::kurtosis_light_client_sync(EthPkgKurtosis::default(), LightClientSync)]
+#[case::kurtosis_relayer_rpc(EthPkgKurtosis::default(), RelayerRpc)]
 #[tokio::test]
 async fn test_beacon_e2e(
     #[case] mut network: impl Network,
     #[case] scenario: impl Scenario,
 ) -> TestResult {
-    network.start().await?;
-
-    let result = {
-        tokio::time::timeout(tokio::time::Duration::from_secs(180), async {
-            loop {
-                if network.health_check().await.is_ok() {
-                    break;
-                }
-                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+    let _guard = logging::init()?;
+
+    // a network that fails to start still gets its artifacts collected and is stopped
+    let result = async {
+        network
+            .start()
+            .instrument(tracing::info_span!("network_start"))
+            .await?;
+
+        // before waiting on a chain the scenario can't relay
+        if let Some(preset) = scenario.preset() {
+            let network_preset = network.network_config().preset;
+
+            if network_preset != Some(preset) {
+                return Err(format!(
+                    "the scenario needs the {:?} preset but the network runs {:?}",
+                    preset, network_preset
+                )
+                .into());
             }
-        })
-        .await?;
+        }
+
+        network
+            .wait_ready(scenario.readiness())
+            .instrument(tracing::info_span!("network_ready"))
+            .await?;
 
         let config = network.network_config();
 
-        scenario.run(config).await
-    };
+        tracing::info!(network = %config.name, "running scenario");
+
+        let timeout = std::env::var(SCENARIO_TIMEOUT)
+            .ok()
+            .and_then(|x| x.parse().ok())
+            .unwrap_or(DEFAULT_SCENARIO_TIMEOUT);
+
+        tokio::time::timeout(
+            core::time::Duration::from_secs(timeout),
+            scenario
+                .run(config)
+                .instrument(tracing::info_span!("scenario")),
+        )
+        .await
+        .map_err(|_| format!("scenario timed out after {}s", timeout))?
+    }
+    .await;
+
+    if let Err(err) = &result {
+        let artifacts_dir = logging::artifacts_dir();
+
+        tracing::error!(?err, artifacts_dir = %artifacts_dir.display(), "scenario failed");
+
+        if let Err(err) = network.on_failure(&artifacts_dir).await {
+            tracing::warn!(?err, "collecting artifacts failed");
+        }
+    }
+
+    // the scenario's error matters more than one from stopping after it
+    if let Err(err) = network.stop().await {
+        if result.is_ok() {
+            return Err(err);
+        }
+
+        tracing::warn!(?err, "stopping the network failed");
+    }
 
-    network.stop().await?;
     result
 }
@@ -1,21 +1,30 @@
 use core::net::{Ipv4Addr, SocketAddr};
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 use alloy::node_bindings::{Anvil, AnvilInstance};
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::Provider;
 use bon::Builder;
 use testresult::TestResult;
 
-use crate::tests::network::{EthereumConfig, EthereumNetwork};
+use crate::tests::network::{ChainControl, EthereumConfig, EthereumNetwork, Readiness};
 
 #[derive(Builder, Debug)]
 pub struct AnvilPoA {
-    #[builder(default = 8545)]
-    pub port: u16,
+    // picked by anvil when unset, known once started
+    pub port: Option<u16>,
+    // 0 automines a block per transaction instead
     #[builder(default = 1)]
     pub block_time: u64,
     #[builder(
         default = "abstract vacuum mammal awkward pudding scene penalty purchase dinner depart evoke puzzle".into()
     )]
     pub mnemonic: String,
+    // a state json, as written by `--dump-state` or `dump_state`, loaded on start
+    pub load_state: Option<PathBuf>,
+    // where stop writes the state json to
+    pub dump_state: Option<PathBuf>,
     pub process: Option<AnvilInstance>,
 }
 
@@ -25,33 +34,209 @@ impl Default for AnvilPoA {
     }
 }
 
+impl AnvilPoA {
+    async fn provider(&self) -> TestResult<impl Provider> {
+        Ok(self.network_config().el_endpoint.provider().await?)
+    }
+
+    // the id is only valid until it or an earlier snapshot is reverted
+    pub async fn snapshot(&self) -> TestResult<U256> {
+        let id = self
+            .provider()
+            .await?
+            .raw_request::<_, U256>("evm_snapshot".into(), ())
+            .await?;
+
+        tracing::debug!(%id, "anvil snapshot");
+
+        Ok(id)
+    }
+
+    pub async fn revert(&self, id: U256) -> TestResult {
+        let reverted = self
+            .provider()
+            .await?
+            .raw_request::<_, bool>("evm_revert".into(), (id,))
+            .await?;
+
+        if !reverted {
+            return Err(format!("no anvil snapshot {}", id).into());
+        }
+
+        tracing::debug!(%id, "anvil reverted");
+
+        Ok(())
+    }
+
+    // the same json `--dump-state` writes, anvil_dumpState serves it gzipped
+    pub async fn dump_state(&self) -> TestResult<Vec<u8>> {
+        let gzipped = self
+            .provider()
+            .await?
+            .raw_request::<_, Bytes>("anvil_dumpState".into(), ())
+            .await?;
+
+        let mut state = vec![];
+        flate2::read::GzDecoder::new(gzipped.as_ref()).read_to_end(&mut state)?;
+
+        Ok(state)
+    }
+
+    pub async fn dump_state_to(&self, path: &Path) -> TestResult {
+        let state = self.dump_state().await?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(path, state)?;
+
+        tracing::info!(path = %path.display(), "anvil state dumped");
+
+        Ok(())
+    }
+}
+
 impl EthereumNetwork for AnvilPoA {
+    #[tracing::instrument(skip_all, fields(port = self.port))]
     async fn start(&mut self) -> TestResult {
         if self.process.is_some() {
             panic!();
         }
 
-        self.process = Some(
-            Anvil::new()
-                .port(self.port)
-                .block_time(self.block_time)
-                .mnemonic(self.mnemonic.clone())
-                .spawn(),
-        );
+        let mut anvil = Anvil::new().mnemonic(self.mnemonic.clone());
+
+        if self.block_time > 0 {
+            anvil = anvil.block_time(self.block_time);
+        }
+
+        if let Some(port) = self.port {
+            anvil = anvil.port(port);
+        }
+
+        if let Some(load_state) = &self.load_state {
+            anvil = anvil.arg("--load-state").arg(load_state);
+        }
+
+        let process = anvil.try_spawn()?;
+
+        tracing::info!(port = process.port(), "anvil is up");
+
+        self.port = Some(process.port());
+        self.process = Some(process);
 
         Ok(())
     }
 
     fn network_config(&self) -> EthereumConfig {
+        let port = self.port.expect("anvil not started");
+
         EthereumConfig {
-            el_socket: SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), self.port),
-            cl_socket: None,
+            name: format!("anvil-{}", port),
+            el_endpoint: SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), port).into(),
+            cl_endpoint: None,
+            el_proof_window: None,
+            archive_el_endpoint: None,
+            preset: None,
             mnemonics: vec![self.mnemonic.clone()],
         }
     }
 
     async fn stop(self) -> TestResult {
+        // the process is killed on drop, so the state can't be dumped on exit
+        if let Some(dump_state) = &self.dump_state {
+            self.dump_state_to(dump_state).await?;
+        }
+
         drop(self.process);
         Ok(())
     }
+
+    // there is no consensus layer, only the el level is reachable
+    async fn ready(&self, readiness: Readiness) -> TestResult {
+        if readiness.needs_cl() {
+            return Err(format!("anvil never reaches {:?}", readiness).into());
+        }
+
+        self.health_check().await
+    }
+}
+
+impl ChainControl for AnvilPoA {
+    async fn mine(&self, blocks: u64) -> TestResult {
+        self.provider()
+            .await?
+            .raw_request::<_, ()>("anvil_mine".into(), (U256::from(blocks),))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_next_block_timestamp(&self, timestamp: u64) -> TestResult {
+        self.provider()
+            .await?
+            .raw_request::<_, ()>("evm_setNextBlockTimestamp".into(), (timestamp,))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn increase_time(&self, seconds: u64) -> TestResult {
+        self.provider()
+            .await?
+            .raw_request::<_, i64>("evm_increaseTime".into(), (U256::from(seconds),))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn impersonate(&self, address: Address) -> TestResult {
+        self.provider()
+            .await?
+            .raw_request::<_, ()>("anvil_impersonateAccount".into(), (address,))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn stop_impersonating(&self, address: Address) -> TestResult {
+        self.provider()
+            .await?
+            .raw_request::<_, ()>("anvil_stopImpersonatingAccount".into(), (address,))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_balance(&self, address: Address, balance: U256) -> TestResult {
+        self.provider()
+            .await?
+            .raw_request::<_, ()>("anvil_setBalance".into(), (address, balance))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_storage_at(&self, address: Address, slot: U256, value: B256) -> TestResult {
+        let set = self
+            .provider()
+            .await?
+            .raw_request::<_, bool>("anvil_setStorageAt".into(), (address, slot, value))
+            .await?;
+
+        if !set {
+            return Err(format!("storage of {} was not set", address).into());
+        }
+
+        Ok(())
+    }
+
+    async fn set_code(&self, address: Address, code: Bytes) -> TestResult {
+        self.provider()
+            .await?
+            .raw_request::<_, ()>("anvil_setCode".into(), (address, code))
+            .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,582 @@
+use core::net::{Ipv4Addr, SocketAddr};
+use core::time::Duration;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use bollard::container::{
+    Config, CreateContainerOptions, LogsOptions, NetworkingConfig, RemoveContainerOptions,
+    StartContainerOptions,
+};
+use bollard::errors::Error as DockerError;
+use bollard::image::BuildImageOptions;
+use bollard::models::{EndpointSettings, HealthConfig, HealthStatusEnum, HostConfig, PortBinding};
+use bollard::network::CreateNetworkOptions;
+use bollard::Docker;
+use bon::Builder;
+use futures::TryStreamExt;
+use serde::Deserialize;
+use testresult::TestResult;
+
+use crate::tests::network::ethpkg::config::{Preset, DEFAULT_MNEMONIC};
+use crate::tests::network::{unique_name, EthereumConfig, EthereumNetwork};
+
+pub const PROJECT_LABEL: &str = "com.docker.compose.project";
+pub const SERVICE_LABEL: &str = "com.docker.compose.service";
+
+// the healthchecks start after 30s and probe every 30s
+pub const HEALTHY_TIMEOUT: Duration = Duration::from_secs(600);
+
+// the subset of the compose format docker/docker-compose.yaml uses
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComposeFile {
+    pub services: BTreeMap<String, ComposeService>,
+    #[serde(default)]
+    pub networks: BTreeMap<String, Option<ComposeNetworkSpec>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComposeService {
+    pub build: Option<BuildSpec>,
+    pub image: String,
+    pub command: Option<String>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub networks: Vec<String>,
+    // `container` or `host:container`, tcp only
+    #[serde(default)]
+    pub ports: Vec<String>,
+    pub healthcheck: Option<Healthcheck>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BuildSpec {
+    pub context: PathBuf,
+    #[serde(default = "default_dockerfile")]
+    pub dockerfile: String,
+}
+
+fn default_dockerfile() -> String {
+    "Dockerfile".into()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Healthcheck {
+    pub test: Vec<String>,
+    pub interval: Option<String>,
+    pub timeout: Option<String>,
+    pub retries: Option<i64>,
+    pub start_period: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComposeNetworkSpec {
+    pub driver: Option<String>,
+}
+
+// compose durations like `30s` or `1m30s`
+pub fn parse_duration(duration: &str) -> anyhow::Result<Duration> {
+    let mut rest = duration.trim();
+    let mut total = Duration::ZERO;
+
+    anyhow::ensure!(!rest.is_empty(), "empty duration");
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .with_context(|| format!("duration {} has no unit", duration))?;
+        let (value, tail) = rest.split_at(digits);
+        let value = value.parse::<u64>()?;
+
+        let unit = tail
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit);
+
+        total += match unit {
+            "ms" => Duration::from_millis(value),
+            "s" => Duration::from_secs(value),
+            "m" => Duration::from_secs(value * 60),
+            "h" => Duration::from_secs(value * 3600),
+            unit => anyhow::bail!("unknown duration unit {} in {}", unit, duration),
+        };
+
+        rest = tail;
+    }
+
+    Ok(total)
+}
+
+impl ComposeFile {
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Ok(serde_yaml::from_slice(&std::fs::read(path)?)?)
+    }
+
+    // every service after the ones it depends on
+    pub fn start_order(&self) -> anyhow::Result<Vec<String>> {
+        let mut order = Vec::<String>::new();
+
+        while order.len() < self.services.len() {
+            let next = self
+                .services
+                .iter()
+                .find(|(name, service)| {
+                    !order.contains(name) && service.depends_on.iter().all(|x| order.contains(x))
+                })
+                .map(|(name, _)| name.clone())
+                .context("services depend on each other or on unknown services")?;
+
+            order.push(next);
+        }
+
+        Ok(order)
+    }
+}
+
+impl ComposeService {
+    // the command is split like a shell would, `--boot-nodes ""` is an empty argument
+    pub fn cmd(&self) -> anyhow::Result<Option<Vec<String>>> {
+        Ok(self
+            .command
+            .as_deref()
+            .map(shell_words::split)
+            .transpose()?)
+    }
+
+    // (host port, container port), docker picks the host port when unset
+    pub fn port_bindings(&self) -> anyhow::Result<Vec<(Option<u16>, u16)>> {
+        self.ports
+            .iter()
+            .map(|port| {
+                let port = port.trim_end_matches("/tcp");
+
+                Ok(match port.split_once(':') {
+                    Some((host, container)) => (Some(host.parse()?), container.parse()?),
+                    None => (None, port.parse()?),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Healthcheck {
+    pub fn health_config(&self) -> anyhow::Result<HealthConfig> {
+        let nanos = |duration: &Option<String>| -> anyhow::Result<Option<i64>> {
+            duration
+                .as_deref()
+                .map(|x| -> anyhow::Result<i64> {
+                    Ok(i64::try_from(parse_duration(x)?.as_nanos())?)
+                })
+                .transpose()
+        };
+
+        Ok(HealthConfig {
+            test: Some(self.test.clone()),
+            interval: nanos(&self.interval)?,
+            timeout: nanos(&self.timeout)?,
+            retries: self.retries,
+            start_period: nanos(&self.start_period)?,
+            ..Default::default()
+        })
+    }
+}
+
+// removing something that is already gone is fine
+fn ignore_missing(result: Result<(), DockerError>) -> Result<(), DockerError> {
+    match result {
+        Err(DockerError::DockerResponseServerError {
+            status_code: 404, ..
+        }) => Ok(()),
+        result => result,
+    }
+}
+
+// the compose stack driven through the docker api, under a project name of its own so
+// parallel tests don't share containers or networks
+#[derive(Builder, Debug)]
+pub struct ComposeNetwork {
+    #[builder(default = Path::new(env!("CARGO_MANIFEST_DIR")).join("docker/docker-compose.yaml"))]
+    pub compose_file: PathBuf,
+    #[builder(default = unique_name("compose"))]
+    pub project: String,
+    #[builder(default = "reth".into())]
+    pub el_service: String,
+    #[builder(default = 8545)]
+    pub el_port: u16,
+    #[builder(default = "lighthouse".into())]
+    pub cl_service: String,
+    #[builder(default = 5052)]
+    pub cl_port: u16,
+    // the published el and cl ports, known once started
+    pub el_socket: Option<SocketAddr>,
+    pub cl_socket: Option<SocketAddr>,
+}
+
+impl Default for ComposeNetwork {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl ComposeNetwork {
+    pub fn container_name(&self, service: &str) -> String {
+        format!("{}-{}", self.project, service)
+    }
+
+    pub fn network_name(&self, network: &str) -> String {
+        format!("{}_{}", self.project, network)
+    }
+
+    fn testnet_dir(&self) -> PathBuf {
+        self.compose_file
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join("data/testnet")
+    }
+
+    // like LocalDevnet, the beacon node can't start from the empty genesis.ssz in docker/data
+    fn check_genesis(&self) -> TestResult {
+        let genesis = self.testnet_dir().join("genesis.ssz");
+
+        if std::fs::metadata(&genesis)?.len() == 0 {
+            return Err(format!(
+                "{} is empty, the compose project has no consensus genesis",
+                genesis.display()
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    fn labels(&self, service: Option<&str>) -> HashMap<String, String> {
+        let mut labels = HashMap::from([(PROJECT_LABEL.to_string(), self.project.clone())]);
+
+        if let Some(service) = service {
+            labels.insert(SERVICE_LABEL.to_string(), service.to_string());
+        }
+
+        labels
+    }
+
+    // the image tags are shared between projects, docker's build cache makes rebuilds cheap
+    async fn build_image(&self, docker: &Docker, build: &BuildSpec, image: &str) -> TestResult {
+        let context = self
+            .compose_file
+            .parent()
+            .context("compose file has no directory")?
+            .join(&build.context);
+
+        let mut archive = tar::Builder::new(vec![]);
+        archive.append_dir_all(".", &context)?;
+
+        let mut build_info = core::pin::pin!(docker.build_image(
+            BuildImageOptions {
+                dockerfile: build.dockerfile.clone(),
+                t: image.to_string(),
+                rm: true,
+                ..Default::default()
+            },
+            None,
+            Some(archive.into_inner()?.into()),
+        ));
+
+        while let Some(info) = build_info.try_next().await? {
+            if let Some(error) = info.error {
+                return Err(format!("building {} failed: {}", image, error).into());
+            }
+
+            if let Some(line) = info.stream {
+                tracing::debug!(image, "{}", line.trim_end());
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn create_service(
+        &self,
+        docker: &Docker,
+        name: &str,
+        service: &ComposeService,
+    ) -> TestResult {
+        let container = self.container_name(name);
+        let bindings = service.port_bindings()?;
+
+        let exposed_ports = bindings
+            .iter()
+            .map(|(_, port)| (format!("{}/tcp", port), HashMap::new()))
+            .collect();
+
+        let port_bindings = bindings
+            .iter()
+            .map(|(host, port)| {
+                (
+                    format!("{}/tcp", port),
+                    Some(vec![PortBinding {
+                        host_ip: Some(Ipv4Addr::LOCALHOST.to_string()),
+                        host_port: Some(host.map(|x| x.to_string()).unwrap_or_default()),
+                    }]),
+                )
+            })
+            .collect();
+
+        // the service name resolves to the container within the project's networks
+        let endpoints_config = service
+            .networks
+            .iter()
+            .map(|network| {
+                (
+                    self.network_name(network),
+                    EndpointSettings {
+                        aliases: Some(vec![name.to_string()]),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+
+        docker
+            .create_container(
+                Some(CreateContainerOptions {
+                    name: container.clone(),
+                    platform: None,
+                }),
+                Config {
+                    image: Some(service.image.clone()),
+                    cmd: service.cmd()?,
+                    labels: Some(self.labels(Some(name))),
+                    exposed_ports: Some(exposed_ports),
+                    healthcheck: service
+                        .healthcheck
+                        .as_ref()
+                        .map(Healthcheck::health_config)
+                        .transpose()?,
+                    host_config: Some(HostConfig {
+                        port_bindings: Some(port_bindings),
+                        ..Default::default()
+                    }),
+                    networking_config: Some(NetworkingConfig { endpoints_config }),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        docker
+            .start_container(&container, None::<StartContainerOptions<String>>)
+            .await?;
+
+        tracing::info!(service = name, container, "compose service started");
+
+        Ok(())
+    }
+
+    async fn up(&self, docker: &Docker, compose: &ComposeFile) -> TestResult {
+        for (name, spec) in &compose.networks {
+            docker
+                .create_network(CreateNetworkOptions {
+                    name: self.network_name(name),
+                    driver: spec
+                        .as_ref()
+                        .and_then(|x| x.driver.clone())
+                        .unwrap_or_else(|| "bridge".into()),
+                    labels: self.labels(None),
+                    ..Default::default()
+                })
+                .await?;
+        }
+
+        for service in compose.services.values() {
+            if let Some(build) = &service.build {
+                self.build_image(docker, build, &service.image).await?;
+            }
+        }
+
+        for name in compose.start_order()? {
+            self.create_service(docker, &name, &compose.services[&name])
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    // every service with a healthcheck reports healthy
+    async fn wait_healthy(&self, docker: &Docker, compose: &ComposeFile) -> TestResult {
+        let services = compose
+            .services
+            .iter()
+            .filter(|(_, service)| service.healthcheck.is_some())
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+
+        tokio::time::timeout(HEALTHY_TIMEOUT, async {
+            loop {
+                let mut pending = vec![];
+
+                for service in &services {
+                    let state = docker
+                        .inspect_container(&self.container_name(service), None)
+                        .await?
+                        .state
+                        .unwrap_or_default();
+
+                    if state.running != Some(true) {
+                        return Err(
+                            format!("{} is not running: {:?}", service, state.status).into()
+                        );
+                    }
+
+                    match state.health.and_then(|x| x.status) {
+                        Some(HealthStatusEnum::HEALTHY) => {}
+                        Some(HealthStatusEnum::UNHEALTHY) => {
+                            return Err(format!("{} is unhealthy", service).into())
+                        }
+                        _ => pending.push(*service),
+                    }
+                }
+
+                if pending.is_empty() {
+                    return TestResult::Ok(());
+                }
+
+                tracing::debug!(?pending, "waiting for healthchecks");
+
+                tokio::time::sleep(Duration::from_secs(2)).await;
+            }
+        })
+        .await
+        .map_err(|_| format!("services not healthy after {:?}", HEALTHY_TIMEOUT))?
+    }
+
+    async fn host_socket(
+        &self,
+        docker: &Docker,
+        service: &str,
+        port: u16,
+    ) -> TestResult<SocketAddr> {
+        let binding = docker
+            .inspect_container(&self.container_name(service), None)
+            .await?
+            .network_settings
+            .and_then(|x| x.ports)
+            .and_then(|mut ports| ports.remove(&format!("{}/tcp", port)))
+            .flatten()
+            .and_then(|x| x.into_iter().next())
+            .with_context(|| format!("{} doesn't publish port {}", service, port))?;
+
+        Ok(SocketAddr::new(
+            Ipv4Addr::LOCALHOST.into(),
+            binding.host_port.context("missing host port")?.parse()?,
+        ))
+    }
+
+    // containers with their anonymous volumes, then the networks
+    async fn down(&self, docker: &Docker, compose: &ComposeFile) -> TestResult {
+        for name in compose.start_order()?.iter().rev() {
+            ignore_missing(
+                docker
+                    .remove_container(
+                        &self.container_name(name),
+                        Some(RemoveContainerOptions {
+                            force: true,
+                            v: true,
+                            ..Default::default()
+                        }),
+                    )
+                    .await,
+            )?;
+        }
+
+        for name in compose.networks.keys() {
+            ignore_missing(docker.remove_network(&self.network_name(name)).await)?;
+        }
+
+        tracing::info!(project = %self.project, "compose project removed");
+
+        Ok(())
+    }
+}
+
+impl EthereumNetwork for ComposeNetwork {
+    #[tracing::instrument(skip_all, fields(project = %self.project))]
+    async fn start(&mut self) -> TestResult {
+        self.check_genesis()?;
+
+        let compose = ComposeFile::from_file(&self.compose_file)?;
+        let docker = Docker::connect_with_local_defaults()?;
+
+        // a half started project is left to stop, after on_failure collected its logs
+        self.up(&docker, &compose).await?;
+        self.wait_healthy(&docker, &compose).await?;
+
+        self.el_socket = Some(
+            self.host_socket(&docker, &self.el_service, self.el_port)
+                .await?,
+        );
+        self.cl_socket = Some(
+            self.host_socket(&docker, &self.cl_service, self.cl_port)
+                .await?,
+        );
+
+        tracing::info!(el_socket = ?self.el_socket, cl_socket = ?self.cl_socket, "compose project is up");
+
+        Ok(())
+    }
+
+    fn network_config(&self) -> EthereumConfig {
+        EthereumConfig {
+            name: self.project.clone(),
+            el_endpoint: self.el_socket.expect("missing el socket").into(),
+            cl_endpoint: self.cl_socket.map(Into::into),
+            el_proof_window: None,
+            archive_el_endpoint: None,
+            // the images are built with docker/data/testnet
+            preset: Preset::from_testnet_config(self.testnet_dir().join("config.yaml")).ok(),
+            // docker/data/genesis.json funds the first account of the default mnemonic
+            mnemonics: vec![DEFAULT_MNEMONIC.into()],
+        }
+    }
+
+    // one log file per service
+    #[tracing::instrument(skip_all, fields(project = %self.project))]
+    async fn on_failure(&mut self, artifacts_dir: &Path) -> TestResult {
+        let compose = ComposeFile::from_file(&self.compose_file)?;
+        let docker = Docker::connect_with_local_defaults()?;
+
+        std::fs::create_dir_all(artifacts_dir)?;
+
+        for name in compose.services.keys() {
+            let container = self.container_name(name);
+
+            let mut logs = core::pin::pin!(docker.logs(
+                &container,
+                Some(LogsOptions::<String> {
+                    stdout: true,
+                    stderr: true,
+                    ..Default::default()
+                }),
+            ));
+
+            let mut file =
+                BufWriter::new(File::create(artifacts_dir.join(format!("{}.log", name)))?);
+
+            while let Some(line) = logs.try_next().await? {
+                write!(file, "{}", line)?;
+            }
+
+            file.flush()?;
+        }
+
+        Ok(())
+    }
+
+    async fn stop(self) -> TestResult {
+        let compose = ComposeFile::from_file(&self.compose_file)?;
+        let docker = Docker::connect_with_local_defaults()?;
+
+        self.down(&docker, &compose).await
+    }
+}
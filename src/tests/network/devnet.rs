@@ -0,0 +1,388 @@
+use core::net::{Ipv4Addr, SocketAddr};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+use anyhow::Context;
+use bon::Builder;
+use testresult::TestResult;
+
+use crate::tests::network::ethpkg::config::{
+    Preset, DEFAULT_MNEMONIC, EL_PROOF_WINDOW, EL_PROOF_WINDOW_FLAG,
+};
+use crate::tests::network::{unique_name, EthereumConfig, EthereumNetwork};
+
+pub const VALIDATOR_PASSWORD: &str = "MySecurePassword";
+
+// in launch order, each logs to <work_dir>/<name>.log
+pub const PROCESSES: [&str; 3] = ["reth", "beacon", "validator"];
+
+// reserved by binding and releasing, the processes take them over right after
+fn free_port() -> std::io::Result<u16> {
+    Ok(std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+        .local_addr()?
+        .port())
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DevnetPorts {
+    pub el_http: u16,
+    pub el_authrpc: u16,
+    pub cl_http: u16,
+    pub cl_p2p: u16,
+}
+
+impl DevnetPorts {
+    pub fn free() -> std::io::Result<Self> {
+        Ok(Self {
+            el_http: free_port()?,
+            el_authrpc: free_port()?,
+            cl_http: free_port()?,
+            cl_p2p: free_port()?,
+        })
+    }
+}
+
+// reth, a lighthouse beacon node and a validator client as child processes, set up
+// like docker/docker-compose.yaml but with the datadirs in a fresh temporary directory
+#[derive(Builder, Debug)]
+pub struct LocalDevnet {
+    #[builder(default = "reth".into())]
+    pub reth_bin: PathBuf,
+    #[builder(default = "lighthouse".into())]
+    pub lighthouse_bin: PathBuf,
+    // genesis.json, jwt.hex and the testnet directory
+    #[builder(default = Path::new(env!("CARGO_MANIFEST_DIR")).join("docker/data"))]
+    pub data_dir: PathBuf,
+    #[builder(default = std::env::temp_dir().join(unique_name("devnet")))]
+    pub work_dir: PathBuf,
+    #[builder(default = 4)]
+    pub validator_count: u64,
+    // free ones are picked when unset, known once started
+    pub ports: Option<DevnetPorts>,
+    // reth, beacon node and validator, stopped in reverse
+    #[builder(skip)]
+    processes: Vec<(&'static str, Child)>,
+    // stop leaves the work_dir with the datadirs and logs behind for inspection
+    #[builder(skip)]
+    failed: bool,
+}
+
+impl Default for LocalDevnet {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl LocalDevnet {
+    fn ports(&self) -> DevnetPorts {
+        self.ports.expect("devnet not started")
+    }
+
+    fn jwt(&self) -> PathBuf {
+        self.data_dir.join("jwt.hex")
+    }
+
+    fn testnet_dir(&self) -> PathBuf {
+        self.data_dir.join("testnet")
+    }
+
+    fn validator_dir(&self) -> PathBuf {
+        self.work_dir.join("validator")
+    }
+
+    fn log_path(&self, name: &str) -> PathBuf {
+        self.work_dir.join(format!("{}.log", name))
+    }
+
+    // docker/data ships an empty genesis.ssz, lighthouse can't start from it and the
+    // network would only time out waiting for the beacon node
+    fn check_genesis(&self) -> TestResult {
+        let genesis = self.testnet_dir().join("genesis.ssz");
+
+        if std::fs::metadata(&genesis)?.len() == 0 {
+            return Err(format!(
+                "{} is empty, the devnet has no consensus genesis",
+                genesis.display()
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    pub fn reth_command(&self) -> Command {
+        let ports = self.ports();
+
+        let mut command = Command::new(&self.reth_bin);
+        command
+            .arg("node")
+            .arg("--chain")
+            .arg(self.data_dir.join("genesis.json"))
+            .arg("--datadir")
+            .arg(self.work_dir.join("reth"))
+            .args(["--http", "--http.addr", "127.0.0.1", "--http.port"])
+            .arg(ports.el_http.to_string())
+            .args(["--http.api", "eth,net,web3,debug"])
+            .args(["--authrpc.addr", "127.0.0.1", "--authrpc.port"])
+            .arg(ports.el_authrpc.to_string())
+            .arg("--authrpc.jwtsecret")
+            .arg(self.jwt())
+            .arg(format!("{}={}", EL_PROOF_WINDOW_FLAG, EL_PROOF_WINDOW))
+            // the default ipc path is shared by every reth on the machine
+            .args([
+                "--ipcdisable",
+                "--disable-discovery",
+                "--nat",
+                "none",
+                "--port",
+                "0",
+            ]);
+
+        command
+    }
+
+    pub fn beacon_node_command(&self) -> Command {
+        let ports = self.ports();
+
+        let mut command = Command::new(&self.lighthouse_bin);
+        command
+            .args(["bn", "--debug-level", "info"])
+            .arg("--datadir")
+            .arg(self.work_dir.join("lighthouse"))
+            .arg("--testnet-dir")
+            .arg(self.testnet_dir())
+            .arg("--execution-endpoint")
+            .arg(format!("http://127.0.0.1:{}", ports.el_authrpc))
+            .arg("--execution-jwt")
+            .arg(self.jwt())
+            .args(["--http", "--http-address", "127.0.0.1", "--http-port"])
+            .arg(ports.cl_http.to_string())
+            .args(["--listen-address", "127.0.0.1", "--port"])
+            .arg(ports.cl_p2p.to_string())
+            .args([
+                "--boot-nodes",
+                "",
+                "--disable-quic",
+                "--disable-upnp",
+                "--private",
+                "--disable-enr-auto-update",
+                "--disable-packet-filter",
+                "--disable-deposit-contract-sync",
+                "--allow-insecure-genesis-sync",
+            ]);
+
+        command
+    }
+
+    pub fn validator_command(&self) -> Command {
+        let mut command = Command::new(&self.lighthouse_bin);
+        command
+            .args(["vc", "--debug-level", "info"])
+            .arg("--datadir")
+            .arg(self.validator_dir())
+            .arg("--testnet-dir")
+            .arg(self.testnet_dir())
+            .arg("--beacon-nodes")
+            .arg(format!("http://127.0.0.1:{}", self.ports().cl_http))
+            .args(["--graffiti", "LocalDevNet", "--init-slashing-protection"]);
+
+        command
+    }
+
+    // the same wallet and keys docker/Dockerfile.validator bakes into its image
+    fn create_validators(&self) -> TestResult {
+        let validator_dir = self.validator_dir();
+        let password_file = validator_dir.join("password.txt");
+
+        std::fs::create_dir_all(&validator_dir)?;
+        std::fs::write(&password_file, VALIDATOR_PASSWORD)?;
+
+        let mut wallet = Command::new(&self.lighthouse_bin);
+        wallet
+            .args(["account", "wallet", "create", "--name", "validator"])
+            .arg("--datadir")
+            .arg(&validator_dir)
+            .arg("--testnet-dir")
+            .arg(self.testnet_dir())
+            .arg("--password-file")
+            .arg(&password_file);
+
+        let mut validators = Command::new(&self.lighthouse_bin);
+        validators
+            .args([
+                "account",
+                "validator",
+                "create",
+                "--wallet-name",
+                "validator",
+            ])
+            .arg("--datadir")
+            .arg(&validator_dir)
+            .arg("--testnet-dir")
+            .arg(self.testnet_dir())
+            .arg("--wallet-password")
+            .arg(&password_file)
+            .arg("--count")
+            .arg(self.validator_count.to_string());
+
+        for mut command in [wallet, validators] {
+            let output = command.output()?;
+
+            if !output.status.success() {
+                return Err(format!(
+                    "{:?} failed: {}",
+                    command,
+                    String::from_utf8_lossy(&output.stderr)
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // stdout and stderr go to <work_dir>/<name>.log
+    fn spawn(&mut self, name: &'static str, mut command: Command) -> TestResult {
+        let log = File::create(self.log_path(name))?;
+
+        let child = command
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()
+            .with_context(|| format!("failed to spawn {:?}", command.get_program()))?;
+
+        tracing::info!(name, pid = child.id(), "devnet process spawned");
+
+        self.processes.push((name, child));
+
+        Ok(())
+    }
+
+    // reth, then the beacon node it drives, then the validator
+    async fn launch(&mut self) -> TestResult {
+        self.create_validators()?;
+
+        let [reth, beacon, validator] = PROCESSES;
+
+        self.spawn(reth, self.reth_command())?;
+        self.spawn(beacon, self.beacon_node_command())?;
+        self.spawn(validator, self.validator_command())?;
+
+        // give a bad flag or a taken port the chance to show up before the readiness wait
+        tokio::time::sleep(core::time::Duration::from_secs(2)).await;
+        self.check_processes()
+    }
+
+    fn kill_processes(&mut self) -> TestResult {
+        while let Some((name, mut child)) = self.processes.pop() {
+            if child.try_wait()?.is_none() {
+                child.kill()?;
+            }
+
+            let status = child.wait()?;
+
+            tracing::debug!(name, %status, "devnet process stopped");
+        }
+
+        Ok(())
+    }
+
+    // a process that exits on its own is misconfigured, its log says why
+    fn check_processes(&mut self) -> TestResult {
+        for (name, child) in &mut self.processes {
+            if let Some(status) = child.try_wait()? {
+                return Err(format!(
+                    "{} exited with {}, see {}",
+                    name,
+                    status,
+                    self.work_dir.join(format!("{}.log", name)).display()
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl EthereumNetwork for LocalDevnet {
+    #[tracing::instrument(skip_all, fields(work_dir = %self.work_dir.display()))]
+    async fn start(&mut self) -> TestResult {
+        if !self.processes.is_empty() {
+            return Err("devnet already started".into());
+        }
+
+        self.check_genesis()?;
+
+        std::fs::create_dir_all(&self.work_dir)?;
+
+        if self.ports.is_none() {
+            self.ports = Some(DevnetPorts::free()?);
+        }
+
+        // nothing is left running when a later process fails to come up
+        if let Err(err) = self.launch().await {
+            self.failed = true;
+
+            if let Err(kill_err) = self.kill_processes() {
+                tracing::warn!(?kill_err, "killing devnet processes failed");
+            }
+
+            return Err(err);
+        }
+
+        tracing::info!(ports = ?self.ports, "devnet is up");
+
+        Ok(())
+    }
+
+    fn network_config(&self) -> EthereumConfig {
+        let ports = self.ports();
+
+        EthereumConfig {
+            name: self
+                .work_dir
+                .file_name()
+                .map(|x| x.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "devnet".into()),
+            el_endpoint: SocketAddr::new(Ipv4Addr::LOCALHOST.into(), ports.el_http).into(),
+            cl_endpoint: Some(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), ports.cl_http).into()),
+            el_proof_window: Some(EL_PROOF_WINDOW),
+            archive_el_endpoint: None,
+            preset: Preset::from_testnet_config(self.testnet_dir().join("config.yaml")).ok(),
+            // docker/data/genesis.json funds the first account of the default mnemonic
+            mnemonics: vec![DEFAULT_MNEMONIC.into()],
+        }
+    }
+
+    // by name, the processes of a failed start are already killed
+    async fn on_failure(&mut self, artifacts_dir: &Path) -> TestResult {
+        self.failed = true;
+
+        std::fs::create_dir_all(artifacts_dir)?;
+
+        for name in PROCESSES {
+            let log_path = self.log_path(name);
+
+            if log_path.exists() {
+                std::fs::copy(&log_path, artifacts_dir.join(format!("{}.log", name)))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn stop(mut self) -> TestResult {
+        self.kill_processes()?;
+
+        if self.failed {
+            tracing::info!(work_dir = %self.work_dir.display(), "keeping the devnet work_dir");
+        } else if self.work_dir.exists() {
+            std::fs::remove_dir_all(&self.work_dir)?;
+        }
+
+        Ok(())
+    }
+}
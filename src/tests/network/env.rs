@@ -1,5 +1,6 @@
 use testresult::TestResult;
 
+use crate::endpoint::Endpoint;
 use crate::tests::network::{EthereumConfig, EthereumNetwork};
 
 pub struct EnvNetwork;
@@ -11,16 +12,19 @@ impl EthereumNetwork for EnvNetwork {
 
     fn network_config(&self) -> EthereumConfig {
         EthereumConfig {
-            el_socket: std::env::var("EL_SOCKET")
-                .expect("missing EL_SOCKET")
-                .parse()
-                .expect("not a socket"),
-            cl_socket: Some(
-                std::env::var("CL_SOCKET")
-                    .expect("missing CL_SOCKET")
-                    .parse()
-                    .expect("not a socket"),
-            ),
+            name: "env".to_string(),
+            el_endpoint: Endpoint::from_env("EL")
+                .expect("invalid EL endpoint")
+                .expect("missing EL_ENDPOINT"),
+            cl_endpoint: Endpoint::from_env("CL").expect("invalid CL endpoint"),
+            el_proof_window: std::env::var("EL_PROOF_WINDOW")
+                .ok()
+                .map(|window| window.parse().expect("not a block count")),
+            archive_el_endpoint: Endpoint::from_env("ARCHIVE_EL")
+                .expect("invalid ARCHIVE_EL endpoint"),
+            preset: std::env::var("PRESET")
+                .ok()
+                .map(|preset| preset.parse().expect("invalid PRESET")),
             mnemonics: vec![std::env::var("MNEMONIC")
                 .expect("missing MNEMONIC")
                 .to_string()],
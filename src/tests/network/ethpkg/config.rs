@@ -0,0 +1,257 @@
+use std::path::Path;
+
+use anyhow::Context;
+use bon::Builder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const DEFAULT_MNEMONIC: &str =
+    "abstract vacuum mammal awkward pudding scene penalty purchase dinner depart evoke puzzle";
+
+// blocks behind the tip reth serves eth_getProof for
+pub const EL_PROOF_WINDOW: u64 = 512;
+
+pub const EL_PROOF_WINDOW_FLAG: &str = "--rpc.eth-proof-window";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClType {
+    Lighthouse,
+    Lodestar,
+    Prysm,
+    Teku,
+    Nimbus,
+    Grandine,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElType {
+    Geth,
+    Reth,
+    Nethermind,
+    Besu,
+    Erigon,
+    Ethereumjs,
+}
+
+impl ClType {
+    // pinned so that a run only uses images already in the local docker cache
+    pub fn image(self) -> &'static str {
+        match self {
+            ClType::Lighthouse => "sigp/lighthouse:v6.0.0",
+            ClType::Lodestar => "chainsafe/lodestar:v1.23.0",
+            ClType::Prysm => "gcr.io/prysmaticlabs/prysm/beacon-chain:v5.1.2",
+            ClType::Teku => "consensys/teku:24.10.3",
+            ClType::Nimbus => "statusim/nimbus-eth2:multiarch-v24.10.0",
+            ClType::Grandine => "sifrai/grandine:1.0.0",
+        }
+    }
+}
+
+impl ElType {
+    // ethereumjs is only published under moving tags, it needs an explicit el_image
+    pub fn image(self) -> Option<&'static str> {
+        match self {
+            ElType::Geth => Some("ethereum/client-go:v1.14.12"),
+            ElType::Reth => Some("ghcr.io/paradigmxyz/reth:v1.1.2"),
+            ElType::Nethermind => Some("nethermind/nethermind:1.29.1"),
+            ElType::Besu => Some("hyperledger/besu:24.10.0"),
+            ElType::Erigon => Some("erigontech/erigon:v2.60.10"),
+            ElType::Ethereumjs => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    Minimal,
+    Mainnet,
+}
+
+impl core::str::FromStr for Preset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "minimal" => Ok(Preset::Minimal),
+            "mainnet" => Ok(Preset::Mainnet),
+            preset => anyhow::bail!("unknown preset {}, expected minimal or mainnet", preset),
+        }
+    }
+}
+
+impl Preset {
+    // the PRESET_BASE of a consensus spec config.yaml, as in a lighthouse testnet dir
+    pub fn from_testnet_config(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        #[derive(Deserialize)]
+        struct TestnetConfig {
+            #[serde(rename = "PRESET_BASE")]
+            preset_base: Preset,
+        }
+
+        let config: TestnetConfig = serde_yaml::from_slice(&std::fs::read(path)?)?;
+
+        Ok(config.preset_base)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdditionalService {
+    Dora,
+    Blockscout,
+    PrometheusGrafana,
+    TxSpammer,
+}
+
+// a group of `count` identical el/cl pairs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Builder)]
+#[serde(default)]
+pub struct Participant {
+    #[builder(default = ClType::Lodestar)]
+    pub cl_type: ClType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cl_image: Option<String>,
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cl_extra_params: Vec<String>,
+    #[builder(default = ElType::Reth)]
+    pub el_type: ElType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub el_image: Option<String>,
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub el_extra_params: Vec<String>,
+    #[builder(default = 1)]
+    pub count: u64,
+    // validator keys per node, the package default when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validator_count: Option<u64>,
+}
+
+impl Default for Participant {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl Participant {
+    // explicit images are kept
+    pub fn pin_images(mut self) -> anyhow::Result<Self> {
+        self.cl_image
+            .get_or_insert_with(|| self.cl_type.image().into());
+
+        if self.el_image.is_none() {
+            self.el_image = Some(
+                self.el_type
+                    .image()
+                    .with_context(|| format!("no pinned {:?} image, set el_image", self.el_type))?
+                    .into(),
+            );
+        }
+
+        Ok(self)
+    }
+
+    pub fn el_proof_window(&self) -> Option<u64> {
+        self.el_extra_params.iter().find_map(|param| {
+            param
+                .strip_prefix(EL_PROOF_WINDOW_FLAG)?
+                .strip_prefix('=')?
+                .parse()
+                .ok()
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Builder)]
+#[serde(default)]
+pub struct NetworkParams {
+    #[builder(default = "kurtosis".into())]
+    pub network: String,
+    #[builder(default = Preset::Minimal)]
+    pub preset: Preset,
+    #[builder(default = 1)]
+    pub seconds_per_slot: u64,
+    #[builder(default = DEFAULT_MNEMONIC.into())]
+    pub preregistered_validator_keys_mnemonic: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_validator_keys_per_node: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capella_fork_epoch: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deneb_fork_epoch: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub electra_fork_epoch: Option<u64>,
+}
+
+impl Default for NetworkParams {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+// the starlark params of github.com/ethpandaops/ethereum-package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Builder)]
+#[serde(default)]
+pub struct EthPkgConfig {
+    // finality doesn't work with lighthouse (default)
+    // transaction indexing with geth (default)
+    #[builder(default = vec![Participant::builder()
+        .el_extra_params(vec![format!("{}={}", EL_PROOF_WINDOW_FLAG, EL_PROOF_WINDOW)])
+        .build()])]
+    pub participants: Vec<Participant>,
+    #[builder(default)]
+    pub network_params: NetworkParams,
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub additional_services: Vec<AdditionalService>,
+    #[builder(default = true)]
+    pub wait_for_finalization: bool,
+}
+
+impl Default for EthPkgConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl EthPkgConfig {
+    pub const ENV: &'static str = "ETHPKG_CONFIG";
+
+    // a yaml (or json) file in the package's own format, unset fields keep their defaults
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        Ok(serde_yaml::from_slice(&std::fs::read(path)?)?)
+    }
+
+    // the smallest window any el serves proofs for, none if one of them doesn't set it
+    pub fn el_proof_window(&self) -> Option<u64> {
+        self.participants
+            .iter()
+            .map(Participant::el_proof_window)
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .min()
+    }
+
+    // the package defaults to moving tags like `latest`, which need a registry
+    pub fn pin_images(mut self) -> anyhow::Result<Self> {
+        self.participants = self
+            .participants
+            .into_iter()
+            .map(Participant::pin_images)
+            .collect::<anyhow::Result<_>>()?;
+        Ok(self)
+    }
+
+    // short enough to fit an enclave name
+    pub fn hash(&self) -> anyhow::Result<String> {
+        Ok(hex::encode(&Sha256::digest(serde_json::to_vec(self)?)[..6]))
+    }
+
+    pub fn mnemonic(&self) -> &str {
+        &self.network_params.preregistered_validator_keys_mnemonic
+    }
+}
@@ -0,0 +1,579 @@
+use core::net::{Ipv4Addr, SocketAddr};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use bon::Builder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use kurtosis_sdk::enclave_api::api_container_service_client::ApiContainerServiceClient;
+use kurtosis_sdk::enclave_api::starlark_run_response_line::RunResponseLine;
+use kurtosis_sdk::enclave_api::{
+    DataChunkMetadata, GetServicesArgs, ImageDownloadMode, RunStarlarkPackageArgs, ServiceInfo,
+    StreamedDataChunk,
+};
+use kurtosis_sdk::engine_api::engine_service_client::EngineServiceClient;
+use kurtosis_sdk::engine_api::{
+    CreateEnclaveArgs, DestroyEnclaveArgs, EnclaveApiContainerStatus, EnclaveContainersStatus,
+    EnclaveInfo, GetServiceLogsArgs,
+};
+use sha1::{Digest, Sha1};
+use testresult::TestResult;
+
+use crate::endpoint::Endpoint;
+use crate::tests::network::ethpkg::config::EthPkgConfig;
+use crate::tests::network::{unique_name, EthereumConfig, EthereumNetwork};
+
+pub mod config;
+
+#[derive(Builder, Debug)]
+pub struct EthPkgKurtosis {
+    #[builder(default = SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 9710))]
+    pub kurtosis_engine_endpoint: SocketAddr,
+    // kurtosis only allows [-A-Za-z0-9] and 60 characters
+    // start picks a unique one when unset, and appends the config hash when reusing
+    pub enclave_name: Option<String>,
+    pub el_socket: Option<SocketAddr>,
+    pub cl_socket: Option<SocketAddr>,
+    #[builder(default)]
+    pub config: EthPkgConfig,
+    // read by start in place of `config`, so a bad file fails the network rather than the case
+    pub config_file: Option<PathBuf>,
+    #[builder(default = Package::from_env())]
+    pub package: Package,
+    #[builder(default = KeepEnclave::from_env())]
+    pub keep_enclave: KeepEnclave,
+    // attach to a healthy enclave with the same config instead of recreating it,
+    // stop leaves it running for the next run
+    #[builder(default = std::env::var_os(REUSE_ENCLAVE_ENV).is_some())]
+    pub reuse_enclave: bool,
+    #[builder(skip)]
+    failed: bool,
+    // the enclave_name start settled on
+    #[builder(skip)]
+    name: String,
+    // held from start until the network is dropped, so test processes running in parallel
+    // take turns on a reused enclave instead of recreating it under each other
+    #[builder(skip)]
+    reuse_lock: Option<File>,
+}
+
+pub const REUSE_ENCLAVE_ENV: &str = "KURTOSIS_REUSE_ENCLAVE";
+
+pub const PACKAGE_ID: &str = "github.com/ethpandaops/ethereum-package";
+
+// the chunk size the kurtosis sdks stream uploads in
+pub const UPLOAD_CHUNK_SIZE: usize = 3 * 1024 * 1024;
+
+// where the starlark package comes from, local packages don't need github
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Package {
+    // a locator, optionally pinned with `@<tag or commit>`
+    Remote(String),
+    // a checkout of the package
+    Dir(PathBuf),
+    // a .tgz holding the package under a single top level directory
+    Archive(PathBuf),
+}
+
+impl Default for Package {
+    fn default() -> Self {
+        Package::Remote(PACKAGE_ID.into())
+    }
+}
+
+impl Package {
+    pub const ENV: &'static str = "ETHPKG_PACKAGE";
+
+    // a directory, a .tgz/.tar.gz or a locator
+    pub fn from_env() -> Self {
+        match std::env::var(Self::ENV) {
+            Ok(package) if Path::new(&package).is_dir() => Package::Dir(package.into()),
+            Ok(package) if package.ends_with(".tgz") || package.ends_with(".tar.gz") => {
+                Package::Archive(package.into())
+            }
+            Ok(package) => Package::Remote(package),
+            Err(_) => Package::default(),
+        }
+    }
+
+    pub fn is_remote(&self) -> bool {
+        matches!(self, Package::Remote(_))
+    }
+
+    // the id the api container resolves the package under, local packages are run as the
+    // upstream package so their kurtosis.yml has to carry its name, as checkouts of it do
+    pub fn id(&self) -> &str {
+        match self {
+            Package::Remote(locator) => locator,
+            Package::Dir(_) | Package::Archive(_) => PACKAGE_ID,
+        }
+    }
+
+    // the gzipped tarball uploaded before the run, none for remote packages
+    pub fn content(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        match self {
+            Package::Remote(_) => Ok(None),
+            Package::Dir(dir) => {
+                let name = PACKAGE_ID.rsplit('/').next().unwrap_or(PACKAGE_ID);
+
+                let mut archive = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+                archive.follow_symlinks(false);
+                archive.append_dir_all(name, dir)?;
+
+                Ok(Some(archive.into_inner()?.finish()?))
+            }
+            Package::Archive(path) => Ok(Some(std::fs::read(path)?)),
+        }
+    }
+
+    // the UploadStarlarkPackage stream, each chunk names the package and carries the sha1 of
+    // the one before, which the api container checks while reassembling it
+    pub fn upload_chunks(&self) -> anyhow::Result<Vec<StreamedDataChunk>> {
+        let Some(content) = self.content()? else {
+            return Ok(vec![]);
+        };
+
+        let mut previous_chunk_hash = String::new();
+
+        Ok(content
+            .chunks(UPLOAD_CHUNK_SIZE)
+            .map(|data| StreamedDataChunk {
+                data: data.to_vec(),
+                previous_chunk_hash: core::mem::replace(
+                    &mut previous_chunk_hash,
+                    hex::encode(Sha1::digest(data)),
+                ),
+                metadata: Some(DataChunkMetadata {
+                    name: self.id().to_string(),
+                }),
+            })
+            .collect())
+    }
+}
+
+// whether stop leaves the enclave running for inspection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeepEnclave {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+impl KeepEnclave {
+    pub const ENV: &'static str = "KURTOSIS_KEEP_ENCLAVE";
+
+    // never, on_failure or always
+    pub fn from_env() -> Self {
+        match std::env::var(Self::ENV).as_deref() {
+            Ok("on_failure") => KeepEnclave::OnFailure,
+            Ok("always") => KeepEnclave::Always,
+            _ => KeepEnclave::Never,
+        }
+    }
+}
+
+// ETHPKG_CONFIG swaps the client mix without touching the cases
+impl Default for EthPkgKurtosis {
+    fn default() -> Self {
+        Self::builder()
+            .maybe_config_file(std::env::var_os(EthPkgConfig::ENV).map(PathBuf::from))
+            .build()
+    }
+}
+
+pub fn get_service_port<'a>(
+    service_info: impl Iterator<Item = (&'a String, &'a ServiceInfo)>,
+    predicate: fn(&str) -> bool,
+    port_name: &str,
+) -> Option<(SocketAddr, SocketAddr)> {
+    for (service_id, info) in service_info {
+        if predicate(service_id) {
+            if let Some(public_port_info) = info.maybe_public_ports.get(port_name) {
+                let private_ip = &info.private_ip_addr;
+                let public_ip = &info.maybe_public_ip_addr;
+                let private_port_info = &info.private_ports[port_name];
+                let private_socket = SocketAddr::new(
+                    private_ip.parse().expect("not an ip"),
+                    private_port_info.number.try_into().expect("not a port"),
+                );
+                let public_socket = SocketAddr::new(
+                    public_ip.parse().expect("not an ip"),
+                    public_port_info.number.try_into().expect("not a port"),
+                );
+                return Some((private_socket, public_socket));
+            }
+        }
+    }
+
+    None
+}
+
+impl EthPkgKurtosis {
+    // a reused name carries the config hash, given or not, so only an identical network is
+    // reused and another config under the same enclave_name gets an enclave of its own
+    pub fn resolve_name(&self) -> anyhow::Result<String> {
+        let name = match (&self.enclave_name, self.reuse_enclave) {
+            (Some(enclave_name), true) => format!("{}-{}", enclave_name, self.config.hash()?),
+            (Some(enclave_name), false) => enclave_name.clone(),
+            (None, true) => format!("ethpkg-{}", self.config.hash()?),
+            (None, false) => unique_name("ethpkg"),
+        };
+
+        anyhow::ensure!(
+            name.len() <= 60,
+            "enclave name {} is longer than kurtosis allows",
+            name
+        );
+
+        Ok(name)
+    }
+
+    // the el- and cl- services of a running enclave
+    async fn discover_services(&mut self, enclave_port: u32) -> TestResult {
+        let mut enclave =
+            ApiContainerServiceClient::connect(format!("https://[::1]:{}", enclave_port)).await?;
+
+        let resp = enclave
+            .get_services(GetServicesArgs {
+                service_identifiers: Default::default(),
+            })
+            .await?
+            .into_inner();
+
+        let el_socket = get_service_port(
+            resp.service_info.iter(),
+            |service_id| service_id.starts_with("el-"),
+            "rpc",
+        )
+        .context("Failed to get el endpoint")?;
+
+        let cl_socket = get_service_port(
+            resp.service_info.iter(),
+            |service_id| service_id.starts_with("cl-"),
+            "http",
+        )
+        .context("Failed to get cl endpoint")?;
+
+        tracing::info!(?el_socket, ?cl_socket, "ethereum-package is up");
+
+        self.el_socket = Some(el_socket.1);
+        self.cl_socket = Some(cl_socket.1);
+
+        Ok(())
+    }
+
+    // attaches to a running enclave, false if it has to be recreated
+    async fn try_reuse(&mut self, enclave_info: &EnclaveInfo) -> bool {
+        if enclave_info.containers_status != EnclaveContainersStatus::Running as i32
+            || enclave_info.api_container_status != EnclaveApiContainerStatus::Running as i32
+        {
+            tracing::warn!("enclave is not running");
+            return false;
+        }
+
+        let Some(host_info) = &enclave_info.api_container_host_machine_info else {
+            return false;
+        };
+
+        if let Err(err) = self
+            .discover_services(host_info.grpc_port_on_host_machine)
+            .await
+        {
+            tracing::warn!(?err, "enclave services are missing");
+            return false;
+        }
+
+        if let Err(err) = self.health_check().await {
+            tracing::warn!(?err, "enclave is unhealthy");
+            return false;
+        }
+
+        true
+    }
+}
+
+impl EthereumNetwork for EthPkgKurtosis {
+    #[tracing::instrument(skip_all, fields(enclave = tracing::field::Empty))]
+    async fn start(&mut self) -> TestResult {
+        if let Some(config_file) = &self.config_file {
+            self.config = EthPkgConfig::from_file(config_file)
+                .with_context(|| format!("invalid {}", config_file.display()))?;
+        }
+
+        // local packages run with pinned images as there may be no registry to resolve tags
+        if !self.package.is_remote() {
+            self.config = self.config.clone().pin_images()?;
+        }
+
+        self.name = self.resolve_name()?;
+
+        tracing::Span::current().record("enclave", &self.name);
+
+        let enclave_name = self.name.clone();
+
+        if self.reuse_enclave {
+            let lock_path = std::env::temp_dir().join(format!("{}.lock", enclave_name));
+
+            tracing::info!(lock = %lock_path.display(), "waiting for the enclave lock");
+
+            self.reuse_lock = Some(
+                tokio::task::spawn_blocking(move || {
+                    let lock = File::create(lock_path)?;
+                    lock.lock()?;
+                    std::io::Result::Ok(lock)
+                })
+                .await??,
+            );
+        }
+
+        // CONNECT TO ENGINE
+        let mut engine =
+            EngineServiceClient::connect(format!("http://{}", self.kurtosis_engine_endpoint))
+                .await?;
+
+        let existing = engine
+            .get_enclaves(())
+            .await?
+            .into_inner()
+            .enclave_info
+            .into_values()
+            .find(|x| x.name == enclave_name);
+
+        // REUSE ENCLAVE IF HEALTHY
+        if let Some(existing) = &existing {
+            if self.reuse_enclave && self.try_reuse(existing).await {
+                tracing::info!(enclave = %enclave_name, "reusing enclave");
+                return Ok(());
+            }
+        }
+
+        // DESTROY ENCLAVE IF EXISTS
+        if existing.is_some() {
+            engine
+                .destroy_enclave(DestroyEnclaveArgs {
+                    enclave_identifier: enclave_name.clone(),
+                })
+                .await?;
+        }
+
+        // CREATE ENCLAVE
+        let create_enclave_response = engine
+            .create_enclave(CreateEnclaveArgs {
+                enclave_name: Some(enclave_name.clone()),
+                api_container_log_level: Some("info".to_string()),
+                api_container_version_tag: None,
+                mode: None,
+                should_apic_run_in_debug_mode: None,
+            })
+            .await?
+            .into_inner();
+
+        // CONNECT TO ENCLAVE
+        let enclave_info = create_enclave_response
+            .enclave_info
+            .expect("Enclave info must be present");
+        let enclave_port = enclave_info
+            .api_container_host_machine_info
+            .expect("Enclave host machine info must be present")
+            .grpc_port_on_host_machine;
+
+        let mut enclave =
+            ApiContainerServiceClient::connect(format!("https://[::1]:{}", enclave_port)).await?;
+
+        // UPLOAD LOCAL PACKAGE
+        // the run then finds it under its id instead of cloning it
+        if !self.package.is_remote() {
+            let chunks = self.package.upload_chunks()?;
+
+            tracing::info!(package = ?self.package, chunks = chunks.len(), "uploading ethereum-package");
+
+            enclave
+                .upload_starlark_package(futures::stream::iter(chunks))
+                .await?;
+        }
+
+        // RUN STARLARK PACKAGE
+        tracing::info!(package = ?self.package, "running ethereum-package");
+
+        let mut run_result = enclave
+            .run_starlark_package(RunStarlarkPackageArgs {
+                package_id: self.package.id().to_string(),
+                serialized_params: Some(serde_json::to_string(&self.config)?),
+                dry_run: None,
+                parallelism: None,
+                clone_package: Some(self.package.is_remote()),
+                relative_path_to_main_file: None,
+                main_function_name: None,
+                experimental_features: vec![],
+                cloud_instance_id: None,
+                cloud_user_id: None,
+                image_download_mode: Some(ImageDownloadMode::Missing.into()),
+                non_blocking_mode: Some(true),
+                github_auth_token: std::env::var("GITHUB_TOKEN")
+                    .ok()
+                    .filter(|_| self.package.is_remote()),
+                starlark_package_content: None,
+            })
+            .await?
+            .into_inner();
+
+        // GET OUTPUT LINES WITH TIMEOUT
+        while let Some(next_message) = run_result.message().await? {
+            match next_message.run_response_line {
+                Some(RunResponseLine::InstructionResult(result)) => {
+                    tracing::debug!(instruction = %result.serialized_instruction_result);
+                }
+                Some(RunResponseLine::RunFinishedEvent(result)) => {
+                    if !result.is_run_successful {
+                        return Err("Kurtosis run failed".into());
+                    }
+                    if let Some(output) = result.serialized_output {
+                        tracing::info!(%output, "kurtosis run finished");
+                    }
+                    break;
+                }
+                _ => continue,
+            }
+        }
+
+        self.discover_services(enclave_port).await
+    }
+
+    fn network_config(&self) -> EthereumConfig {
+        EthereumConfig {
+            name: self.name.clone(),
+            el_endpoint: self.el_socket.expect("missing el socket").into(),
+            cl_endpoint: self.cl_socket.map(Endpoint::from),
+            el_proof_window: self.config.el_proof_window(),
+            archive_el_endpoint: None,
+            preset: Some(self.config.network_params.preset),
+            mnemonics: vec![self.config.mnemonic().to_string()],
+        }
+    }
+
+    // every service's logs, one file per service, next to the package params
+    #[tracing::instrument(skip_all, fields(enclave = %self.name))]
+    async fn on_failure(&mut self, artifacts_dir: &Path) -> TestResult {
+        self.failed = true;
+
+        std::fs::create_dir_all(artifacts_dir)?;
+        std::fs::write(
+            artifacts_dir.join("ethpkg-params.json"),
+            serde_json::to_vec_pretty(&self.config)?,
+        )?;
+
+        let mut engine =
+            EngineServiceClient::connect(format!("http://{}", self.kurtosis_engine_endpoint))
+                .await?;
+
+        let enclave_port = engine
+            .get_enclaves(())
+            .await?
+            .into_inner()
+            .enclave_info
+            .into_values()
+            .find(|x| x.name == self.name)
+            .and_then(|x| x.api_container_host_machine_info)
+            .context("enclave is gone")?
+            .grpc_port_on_host_machine;
+
+        let mut enclave =
+            ApiContainerServiceClient::connect(format!("https://[::1]:{}", enclave_port)).await?;
+
+        // uuid -> name, logs are keyed by uuid
+        let names = enclave
+            .get_services(GetServicesArgs {
+                service_identifiers: Default::default(),
+            })
+            .await?
+            .into_inner()
+            .service_info
+            .into_values()
+            .map(|info| (info.service_uuid, info.name))
+            .collect::<HashMap<_, _>>();
+
+        let mut logs = engine
+            .get_service_logs(GetServiceLogsArgs {
+                enclave_identifier: self.name.clone(),
+                service_uuid_set: names.keys().map(|uuid| (uuid.clone(), true)).collect(),
+                follow_logs: false,
+                conjunctive_filters: vec![],
+                return_all_logs: true,
+                num_log_lines: 0,
+            })
+            .await?
+            .into_inner();
+
+        let mut files = HashMap::new();
+
+        while let Some(response) = logs.message().await? {
+            for (uuid, log_line) in response.service_logs_by_service_uuid {
+                let file = match files.entry(uuid) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        let name = names.get(entry.key()).unwrap_or(entry.key());
+                        let file = File::create(artifacts_dir.join(format!("{}.log", name)))?;
+                        entry.insert(BufWriter::new(file))
+                    }
+                };
+
+                for line in log_line.line {
+                    writeln!(file, "{}", line)?;
+                }
+            }
+        }
+
+        for file in files.values_mut() {
+            file.flush()?;
+        }
+
+        tracing::info!(
+            services = files.len(),
+            artifacts_dir = %artifacts_dir.display(),
+            "collected service logs"
+        );
+
+        Ok(())
+    }
+
+    async fn stop(self) -> TestResult {
+        let enclave_name = self.name.clone();
+
+        if self.reuse_enclave {
+            tracing::info!(enclave = %enclave_name, "leaving enclave for reuse");
+
+            return Ok(());
+        }
+
+        if self.keep_enclave == KeepEnclave::Always
+            || (self.keep_enclave == KeepEnclave::OnFailure && self.failed)
+        {
+            tracing::warn!(
+                enclave = %enclave_name,
+                "keeping enclave, remove it with `kurtosis enclave rm -f {}`",
+                enclave_name
+            );
+
+            return Ok(());
+        }
+
+        // CONNECT TO ENGINE
+        let mut engine =
+            EngineServiceClient::connect(format!("http://{}", self.kurtosis_engine_endpoint))
+                .await?;
+
+        // DESTROY ENCLAVE
+        engine
+            .destroy_enclave(DestroyEnclaveArgs {
+                enclave_identifier: enclave_name,
+            })
+            .await?;
+
+        Ok(())
+    }
+}
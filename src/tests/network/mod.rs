@@ -1,20 +1,126 @@
 use core::future::Future;
 use core::marker::Sync;
-use core::net::SocketAddr;
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::path::Path;
 
-use alloy::providers::{Provider, ProviderBuilder};
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::Provider;
+use anyhow::Context;
+use serde_json::Value;
 use testresult::TestResult;
 
+use crate::endpoint::Endpoint;
+use crate::tests::network::ethpkg::config::Preset;
+
 pub mod anvil;
+pub mod compose;
+pub mod devnet;
 pub mod env;
 pub mod ethpkg;
 
 pub struct EthereumConfig {
-    pub el_socket: SocketAddr,
-    pub cl_socket: Option<SocketAddr>,
+    // the enclave or instance the endpoints belong to, unique per test
+    pub name: String,
+    pub el_endpoint: Endpoint,
+    pub cl_endpoint: Option<Endpoint>,
+    // blocks behind the tip the el serves historical proofs for
+    pub el_proof_window: Option<u64>,
+    // serves historical proofs outside of el_proof_window
+    pub archive_el_endpoint: Option<Endpoint>,
+    // the consensus preset of the beacon chain, none without one
+    pub preset: Option<Preset>,
     pub mnemonics: Vec<String>,
 }
 
+// unique across the processes nextest runs in parallel and the networks of one process
+pub fn unique_name(prefix: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|x| x.subsec_nanos())
+        .unwrap_or_default();
+
+    format!(
+        "{}-{}-{}-{:x}",
+        prefix,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed),
+        nanos
+    )
+}
+
+// how far a network has to be before a scenario runs, each level implies the ones before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Readiness {
+    ElUp,
+    // /eth/v1/node/syncing reports the head as synced
+    ClSynced,
+    // the first finalized checkpoint exists
+    Finalized,
+    // the finalized checkpoint is in at least this sync committee period
+    FinalizedPeriod(u64),
+}
+
+impl Readiness {
+    pub fn needs_cl(&self) -> bool {
+        *self > Readiness::ElUp
+    }
+
+    // finality takes a few epochs and every period is another 64 minimal epochs
+    pub fn timeout(&self) -> core::time::Duration {
+        core::time::Duration::from_secs(match self {
+            Readiness::ElUp | Readiness::ClSynced => 180,
+            Readiness::Finalized => 900,
+            Readiness::FinalizedPeriod(period) => 900 + 600 * period,
+        })
+    }
+}
+
+async fn beacon_get(cl_endpoint: &Endpoint, path: &str) -> TestResult<Value> {
+    Ok(reqwest::Client::new()
+        .get(cl_endpoint.join(path)?)
+        .headers(cl_endpoint.header_map()?)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
+pub async fn cl_synced(cl_endpoint: &Endpoint) -> TestResult {
+    let syncing = beacon_get(cl_endpoint, "/eth/v1/node/syncing").await?;
+
+    if syncing["data"]["is_syncing"].as_bool() != Some(false) {
+        return Err(format!("cl is syncing: {}", syncing["data"]).into());
+    }
+
+    Ok(())
+}
+
+// zero until the first checkpoint is finalized
+pub async fn finalized_epoch(cl_endpoint: &Endpoint) -> TestResult<u64> {
+    let checkpoints = beacon_get(
+        cl_endpoint,
+        "/eth/v1/beacon/states/head/finality_checkpoints",
+    )
+    .await?;
+
+    Ok(checkpoints["data"]["finalized"]["epoch"]
+        .as_str()
+        .context("finalized epoch")?
+        .parse()?)
+}
+
+pub async fn epochs_per_sync_committee_period(cl_endpoint: &Endpoint) -> TestResult<u64> {
+    let spec = beacon_get(cl_endpoint, "/eth/v1/config/spec").await?;
+
+    Ok(spec["data"]["EPOCHS_PER_SYNC_COMMITTEE_PERIOD"]
+        .as_str()
+        .context("EPOCHS_PER_SYNC_COMMITTEE_PERIOD")?
+        .parse()?)
+}
+
 pub trait EthereumNetwork: Sync + Send + Sized {
     fn start(&mut self) -> impl Future<Output = TestResult> + Send;
     fn network_config(&self) -> EthereumConfig;
@@ -22,13 +128,112 @@ pub trait EthereumNetwork: Sync + Send + Sized {
 
     fn health_check(&self) -> impl Future<Output = TestResult> + Send {
         async {
-            let EthereumConfig { el_socket, .. } = self.network_config();
-            let provider = ProviderBuilder::new()
-                .with_recommended_fillers()
-                .on_builtin(&format!("http://{}", el_socket))
-                .await?;
+            let EthereumConfig { el_endpoint, .. } = self.network_config();
+            let provider = el_endpoint.provider().await?;
             provider.get_chain_id().await?;
             Ok(())
         }
     }
+
+    // a single probe, errors until the network reached `readiness`
+    fn ready(&self, readiness: Readiness) -> impl Future<Output = TestResult> + Send {
+        async move {
+            self.health_check().await?;
+
+            if !readiness.needs_cl() {
+                return Ok(());
+            }
+
+            let EthereumConfig { cl_endpoint, .. } = self.network_config();
+            let cl_endpoint = cl_endpoint.context("no cl_endpoint")?;
+
+            cl_synced(&cl_endpoint).await?;
+
+            let required_epoch = match readiness {
+                Readiness::ElUp | Readiness::ClSynced => return Ok(()),
+                Readiness::Finalized => 1,
+                Readiness::FinalizedPeriod(period) => {
+                    (period * epochs_per_sync_committee_period(&cl_endpoint).await?).max(1)
+                }
+            };
+
+            let finalized_epoch = finalized_epoch(&cl_endpoint).await?;
+
+            if finalized_epoch < required_epoch {
+                return Err(format!(
+                    "finalized epoch {} is before epoch {}",
+                    finalized_epoch, required_epoch
+                )
+                .into());
+            }
+
+            Ok(())
+        }
+    }
+
+    // called when a scenario failed or timed out, before stop
+    fn on_failure(&mut self, _artifacts_dir: &Path) -> impl Future<Output = TestResult> + Send {
+        async { Ok(()) }
+    }
+
+    fn wait_ready(&self, readiness: Readiness) -> impl Future<Output = TestResult> + Send {
+        async move {
+            if readiness.needs_cl() && self.network_config().cl_endpoint.is_none() {
+                return Err(format!("{:?} needs a consensus layer", readiness).into());
+            }
+
+            let mut last_err = None;
+
+            let waited = tokio::time::timeout(readiness.timeout(), async {
+                loop {
+                    match self.ready(readiness).await {
+                        Ok(()) => break,
+                        Err(err) => {
+                            tracing::debug!(?readiness, ?err, "network not ready");
+                            last_err = Some(err);
+                        }
+                    }
+
+                    tokio::time::sleep(core::time::Duration::from_secs(2)).await;
+                }
+            })
+            .await;
+
+            if waited.is_err() {
+                return Err(format!(
+                    "network not {:?} after {:?}: {:?}",
+                    readiness,
+                    readiness.timeout(),
+                    last_err
+                )
+                .into());
+            }
+
+            tracing::info!(?readiness, "network ready");
+
+            Ok(())
+        }
+    }
+}
+
+// cheatcodes for steering the chain, only dev networks like anvil implement it
+pub trait ChainControl: EthereumNetwork {
+    fn mine(&self, blocks: u64) -> impl Future<Output = TestResult> + Send;
+    fn set_next_block_timestamp(&self, timestamp: u64) -> impl Future<Output = TestResult> + Send;
+    fn increase_time(&self, seconds: u64) -> impl Future<Output = TestResult> + Send;
+    // transactions from `address` are accepted unsigned until stop_impersonating
+    fn impersonate(&self, address: Address) -> impl Future<Output = TestResult> + Send;
+    fn stop_impersonating(&self, address: Address) -> impl Future<Output = TestResult> + Send;
+    fn set_balance(
+        &self,
+        address: Address,
+        balance: U256,
+    ) -> impl Future<Output = TestResult> + Send;
+    fn set_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        value: B256,
+    ) -> impl Future<Output = TestResult> + Send;
+    fn set_code(&self, address: Address, code: Bytes) -> impl Future<Output = TestResult> + Send;
 }
@@ -0,0 +1,411 @@
+use core::time::Duration;
+
+use alloy::primitives::{keccak256, Address, B256};
+use anyhow::Context;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::get;
+use axum::Json;
+use ics008_wasm_client::MerklePath;
+use protos::union::ibc::lightclients::ethereum::v1::{
+    LightClientUpdate as LightClientUpdateProto, SyncCommittee as SyncCommitteeProto,
+};
+use serde_json::json;
+use testresult::TestResult;
+use unionlabs::ethereum::config::Minimal;
+use unionlabs::ethereum::IBC_HANDLER_COMMITMENTS_SLOT;
+use unionlabs::hash::H256;
+use unionlabs::ibc::core::client::height::Height;
+use unionlabs::ibc::lightclients::ethereum::account_proof::AccountProof;
+use unionlabs::ibc::lightclients::ethereum::account_update::AccountUpdate;
+use unionlabs::ibc::lightclients::ethereum::header::Header;
+use unionlabs::ibc::lightclients::ethereum::light_client_update::UnboundedLightClientUpdate;
+use unionlabs::ibc::lightclients::ethereum::trusted_sync_committee::{
+    ActiveSyncCommittee, TrustedSyncCommittee,
+};
+use unionlabs::uint::U256;
+
+use crate::cosmos::mock::OptimisticHeads;
+use crate::endpoint::{Auth, Endpoint};
+use crate::relayer::daemon::{Counterparty, Daemon};
+use crate::relayer::optimistic::{OptimisticHeader, OptimisticUpdate, RelayedHeader};
+use crate::relayer::ssz::{light_client_update, Fork};
+use crate::relayer::{commitment_key, OutsideProofWindow, Relayer};
+use crate::tests::ssz::light_client_header;
+
+const SYNC_COMMITTEE_SIZE: usize = 32;
+
+const PATH: &str = "commitments/ports/transfer/channels/channel-0/sequences/1";
+
+fn merkle_path(key_path: &[&str]) -> MerklePath {
+    MerklePath {
+        key_path: key_path.iter().map(|x| x.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_commitment_key() -> TestResult {
+    // solidity mapping layout: keccak256(keccak256(path) ++ slot)
+    let expected = U256::from_be_bytes(
+        keccak256(
+            [
+                keccak256(PATH.as_bytes()).as_slice(),
+                &IBC_HANDLER_COMMITMENTS_SLOT.to_be_bytes(),
+            ]
+            .concat(),
+        )
+        .0,
+    );
+
+    assert_eq!(commitment_key(&merkle_path(&[PATH]))?, expected);
+
+    // segments are joined without a separator
+    let (prefix, rest) = PATH.split_at(11);
+    assert_eq!(commitment_key(&merkle_path(&[prefix, rest]))?, expected);
+
+    assert_ne!(
+        commitment_key(&merkle_path(&["commitments/ports/transfer"]))?,
+        expected
+    );
+    assert!(commitment_key(&merkle_path(&[])).is_err());
+
+    Ok(())
+}
+
+// a deneb update finalizing `finalized_slot`, nothing in it is signed or provable
+pub fn update(finalized_slot: u64) -> anyhow::Result<UnboundedLightClientUpdate> {
+    let attested_header = light_client_header(finalized_slot + 16, &[]);
+    let finalized_header = light_client_header(finalized_slot, &[]);
+
+    let fixed_len = 4 + 48 * (SYNC_COMMITTEE_SIZE + 1) + 32 * 5 + 4 + 32 * 6 + 4 + 96 + 8;
+
+    let bytes = [
+        u32::try_from(fixed_len)?.to_le_bytes().to_vec(),
+        vec![8; 48 * (SYNC_COMMITTEE_SIZE + 1)],
+        vec![9; 32 * 5],
+        u32::try_from(fixed_len + attested_header.len())?
+            .to_le_bytes()
+            .to_vec(),
+        vec![10; 32 * 6],
+        vec![0xff; 4 + 96],
+        (finalized_slot + 17).to_le_bytes().to_vec(),
+        attested_header,
+        finalized_header,
+    ]
+    .concat();
+
+    Ok(serde_json::from_value(light_client_update(
+        &bytes,
+        Fork::Deneb,
+        SYNC_COMMITTEE_SIZE,
+    )?)?)
+}
+
+fn trusted_sync_committee(
+    slot: u64,
+    update: &UnboundedLightClientUpdate,
+) -> anyhow::Result<TrustedSyncCommittee<Minimal>> {
+    let sync_committee = update
+        .next_sync_committee
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("missing sync committee"))?;
+
+    Ok(TrustedSyncCommittee {
+        trusted_height: Height {
+            revision_number: 0,
+            revision_height: slot,
+        },
+        sync_committee: ActiveSyncCommittee::Current(
+            SyncCommitteeProto::from(sync_committee).try_into()?,
+        ),
+    })
+}
+
+// headers chained like Relayer::header builds them, with the trust after the last one
+fn header_chain(
+    trusted_slot: u64,
+    finalized_slots: &[u64],
+) -> anyhow::Result<(Vec<Header<Minimal>>, TrustedSyncCommittee<Minimal>)> {
+    let mut headers = vec![];
+    let mut trusted_slot = trusted_slot;
+
+    for &finalized_slot in finalized_slots {
+        let update = update(finalized_slot)?;
+
+        headers.push(Header {
+            trusted_sync_committee: trusted_sync_committee(trusted_slot, &update)?,
+            consensus_update: LightClientUpdateProto::from(update).try_into()?,
+            account_update: AccountUpdate {
+                account_proof: AccountProof {
+                    storage_root: B256::ZERO.into(),
+                    proof: vec![],
+                },
+            },
+        });
+
+        trusted_slot = finalized_slot;
+    }
+
+    let last = update(trusted_slot)?;
+
+    Ok((headers, trusted_sync_committee(trusted_slot, &last)?))
+}
+
+// accepts headers until `fail_at` of them went through
+struct FlakyCounterparty {
+    latest_height: u64,
+    accepted: usize,
+    fail_at: usize,
+}
+
+impl Counterparty<Minimal> for FlakyCounterparty {
+    async fn latest_height(&self) -> anyhow::Result<u64> {
+        Ok(self.latest_height)
+    }
+
+    async fn latest_timestamp(&self) -> anyhow::Result<u64> {
+        Ok(0)
+    }
+
+    async fn submit(&mut self, header: Header<Minimal>) -> anyhow::Result<()> {
+        anyhow::ensure!(self.accepted < self.fail_at, "header rejected");
+
+        anyhow::ensure!(
+            header.trusted_sync_committee.trusted_height.revision_height == self.latest_height,
+            "header is not built on the latest height",
+        );
+
+        self.latest_height = header.consensus_update.finalized_header.beacon.slot;
+        self.accepted += 1;
+
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_daemon_partial_submit() -> TestResult {
+    let (headers, trusted_after) = header_chain(8, &[16, 24, 32])?;
+
+    let mut daemon = Daemon::builder()
+        .relayer(
+            Relayer::<Minimal>::builder()
+                .ibc_handler_address(Address::ZERO)
+                .cl_endpoint("127.0.0.1:1".parse::<Endpoint>()?)
+                .el_endpoint("127.0.0.1:1".parse::<Endpoint>()?)
+                .build(),
+        )
+        .counterparty(FlakyCounterparty {
+            latest_height: 8,
+            accepted: 0,
+            fail_at: 2,
+        })
+        .trusted_sync_committee(headers[0].trusted_sync_committee.clone())
+        .trusting_period(Duration::from_secs(3600))
+        .build();
+
+    // the third header fails, the first two are applied
+    assert!(daemon
+        .submit_headers(headers.clone(), trusted_after.clone())
+        .await
+        .is_err());
+
+    assert_eq!(daemon.counterparty.latest_height, 24);
+    assert_eq!(
+        daemon.trusted_sync_committee.trusted_height.revision_height,
+        daemon.counterparty.latest_height
+    );
+
+    // the remaining header goes through on the recovered trust
+    daemon.counterparty.fail_at = usize::MAX;
+    daemon
+        .submit_headers(headers[2..].to_vec(), trusted_after)
+        .await?;
+
+    assert_eq!(daemon.counterparty.latest_height, 32);
+    assert_eq!(
+        daemon.trusted_sync_committee.trusted_height.revision_height,
+        32
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_optimistic_heads() -> TestResult {
+    let mut heads = OptimisticHeads::default();
+
+    let head = update(16)?.attested_header;
+    let slot = head.beacon.slot;
+
+    assert!(heads.insert(slot, head.clone()).is_err());
+    assert!(heads.insert(8, head.clone())?.is_none());
+    assert!(heads.insert(8, update(24)?.attested_header)?.is_none());
+
+    // another header at the same slot, as after a reorg, supersedes the tracked one
+    let mut reorged = head.clone();
+    reorged.beacon.state_root = B256::repeat_byte(1).into();
+
+    let replaced = heads
+        .insert(8, reorged.clone())?
+        .context("nothing replaced")?;
+    assert_eq!(replaced.beacon.state_root, head.beacon.state_root);
+    assert_eq!(
+        heads.get(slot).map(|x| x.beacon.state_root),
+        Some(reorged.beacon.state_root)
+    );
+
+    // finality drops everything at or below it
+    heads.finalize(slot);
+    assert_eq!(heads.len(), 1);
+    assert!(heads.get(slot).is_none());
+
+    heads.finalize(u64::MAX - 1);
+    assert!(heads.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_optimistic_header_rejected_by_default() -> TestResult {
+    let (headers, _) = header_chain(8, &[16])?;
+    let update = update(16)?;
+
+    let mut counterparty = FlakyCounterparty {
+        latest_height: 8,
+        accepted: 0,
+        fail_at: usize::MAX,
+    };
+
+    let optimistic = RelayedHeader::Optimistic(OptimisticHeader {
+        trusted_sync_committee: headers[0].trusted_sync_committee.clone(),
+        optimistic_update: OptimisticUpdate {
+            attested_header: update.attested_header,
+            sync_aggregate: update.sync_aggregate,
+            signature_slot: update.signature_slot,
+        },
+        account_update: headers[0].account_update.clone(),
+    });
+
+    // a counterparty that only follows finality never sees it
+    assert!(counterparty.submit_relayed(optimistic).await.is_err());
+    assert_eq!(counterparty.accepted, 0);
+
+    counterparty
+        .submit_relayed(RelayedHeader::Finalized(headers[0].clone()))
+        .await?;
+    assert_eq!(counterparty.latest_height, 16);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_misbehaviour_unsupported() -> TestResult {
+    let relayer = Relayer::<Minimal>::builder()
+        .ibc_handler_address(Address::ZERO)
+        .cl_endpoint("127.0.0.1:1".parse::<Endpoint>()?)
+        .el_endpoint("127.0.0.1:1".parse::<Endpoint>()?)
+        .build();
+
+    // an error, not a panic, and without touching the endpoints
+    assert!(relayer.misbehaviour().await.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_archive_endpoint_for() -> TestResult {
+    let endpoint = "127.0.0.1:1".parse::<Endpoint>()?;
+    let archive = "127.0.0.2:1".parse::<Endpoint>()?;
+
+    // only the window and the archive endpoint matter, nothing is requested
+    let relayer = |proof_window: Option<u64>, archive: Option<Endpoint>| {
+        Relayer::<Minimal>::builder()
+            .ibc_handler_address(Address::ZERO)
+            .cl_endpoint(endpoint.clone())
+            .el_endpoint(endpoint.clone())
+            .maybe_proof_window(proof_window)
+            .maybe_archive_el_endpoint(archive)
+            .build()
+    };
+
+    // the window is inclusive, a height exactly proof_window behind the tip is still served
+    let windowed = relayer(Some(512), Some(archive.clone()));
+    assert_eq!(windowed.archive_endpoint_for(1000, 1000), Ok(None));
+    assert_eq!(windowed.archive_endpoint_for(488, 1000), Ok(None));
+    assert_eq!(windowed.archive_endpoint_for(487, 1000), Ok(Some(&archive)));
+    assert_eq!(windowed.archive_endpoint_for(0, 1000), Ok(Some(&archive)));
+
+    // a height ahead of the tip the el hasn't seen yet is not historical
+    assert_eq!(windowed.archive_endpoint_for(1001, 1000), Ok(None));
+
+    let no_archive = relayer(Some(512), None);
+    assert_eq!(no_archive.archive_endpoint_for(488, 1000), Ok(None));
+    assert_eq!(
+        no_archive.archive_endpoint_for(487, 1000),
+        Err(OutsideProofWindow {
+            execution_height: 487,
+            latest_height: 1000,
+            proof_window: 512,
+        })
+    );
+
+    // without a window the el serves everything, the archive is never used
+    let unwindowed = relayer(None, Some(archive));
+    assert_eq!(unwindowed.archive_endpoint_for(0, 1000), Ok(None));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_beacon_calls_send_auth() -> TestResult {
+    let authorized = |headers: &HeaderMap| {
+        headers
+            .get(reqwest::header::AUTHORIZATION)
+            .is_some_and(|x| x == "Bearer token")
+    };
+
+    let router = axum::Router::new()
+        .route(
+            "/eth/v1/beacon/headers/8",
+            get(move |headers: HeaderMap| async move {
+                if !authorized(&headers) {
+                    return Err(StatusCode::UNAUTHORIZED);
+                }
+
+                Ok(Json(json!({ "data": { "root": format!("0x{}", "ab".repeat(32)) } })))
+            }),
+        )
+        .route(
+            "/eth/v2/beacon/blocks/8",
+            get(move |headers: HeaderMap| async move {
+                if !authorized(&headers) {
+                    return Err(StatusCode::UNAUTHORIZED);
+                }
+
+                Ok(Json(json!({
+                    "data": { "message": { "body": { "execution_payload": { "block_number": "42" } } } }
+                })))
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}", listener.local_addr()?).parse()?;
+
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let relayer = Relayer::<Minimal>::builder()
+        .ibc_handler_address(Address::ZERO)
+        .cl_endpoint(
+            Endpoint::builder()
+                .url(url)
+                .auth(Auth::Bearer("token".into()))
+                .build(),
+        )
+        .el_endpoint("127.0.0.1:1".parse::<Endpoint>()?)
+        .build();
+
+    let root: H256 = B256::repeat_byte(0xab).into();
+    assert_eq!(relayer.block_root(8).await?, root);
+    assert_eq!(relayer.execution_height(8).await?, 42);
+
+    Ok(())
+}
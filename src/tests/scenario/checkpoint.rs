@@ -0,0 +1,81 @@
+use anyhow::Context;
+use testresult::TestResult;
+use unionlabs::ethereum::config::Minimal;
+
+use crate::relayer::Relayer;
+use crate::tests::network::ethpkg::config::Preset;
+use crate::tests::network::{EthereumConfig, Readiness};
+use crate::tests::scenario::relayer::{deploy_ibc_handler, finalized_slot};
+use crate::tests::scenario::Scenario;
+
+pub struct CheckpointInit;
+
+impl Scenario for CheckpointInit {
+    fn readiness(&self) -> Readiness {
+        Readiness::FinalizedPeriod(2)
+    }
+
+    fn preset(&self) -> Option<Preset> {
+        Some(Preset::Minimal)
+    }
+
+    async fn run(&self, config: EthereumConfig) -> TestResult {
+        let EthereumConfig {
+            el_endpoint,
+            cl_endpoint,
+            el_proof_window,
+            archive_el_endpoint,
+            mnemonics,
+            ..
+        } = config;
+
+        let cl_endpoint = cl_endpoint.context("no cl_endpoint")?;
+
+        let beacon_client =
+            beacon_api::client::BeaconApiClient::new(cl_endpoint.to_string()).await?;
+
+        let spec = beacon_client.spec().await?.data;
+
+        let finalized_slot = finalized_slot(&cl_endpoint).await?;
+
+        let ibc_handler_address = deploy_ibc_handler(&el_endpoint, &mnemonics[0]).await?;
+
+        let relayer = Relayer::<Minimal>::builder()
+            .ibc_handler_address(ibc_handler_address)
+            .cl_endpoint(cl_endpoint)
+            .el_endpoint(el_endpoint)
+            .maybe_proof_window(el_proof_window)
+            .maybe_archive_el_endpoint(archive_el_endpoint)
+            .build();
+
+        let (checkpoint_slot, checkpoint_root) = relayer.checkpoint(finalized_slot).await?;
+
+        tracing::info!(
+            root = %serde_json::to_string(&checkpoint_root)?,
+            slot = checkpoint_slot,
+            near = finalized_slot,
+            "checkpoint"
+        );
+
+        assert_eq!(checkpoint_slot % spec.slots_per_epoch, 0);
+        assert!(checkpoint_slot <= finalized_slot);
+
+        let (client_state, consensus_state, trusted_sync_committee) =
+            relayer.initialize_from_checkpoint(checkpoint_root).await?;
+
+        assert_eq!(client_state.latest_slot, checkpoint_slot);
+        assert_eq!(consensus_state.slot, checkpoint_slot);
+
+        tokio::time::sleep(core::time::Duration::from_secs(
+            spec.seconds_per_slot * spec.period(),
+        ))
+        .await;
+
+        // the seeded state must be usable for catching up
+        let (headers, _) = relayer.header(trusted_sync_committee).await?;
+
+        assert!(!headers.is_empty());
+
+        Ok(())
+    }
+}
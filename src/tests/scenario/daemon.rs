@@ -0,0 +1,110 @@
+use anyhow::Context;
+use testresult::TestResult;
+use tokio_util::sync::CancellationToken;
+use unionlabs::ethereum::config::Minimal;
+
+use crate::cosmos::mock::{ClientStatus, MockClientHandle, MockCounterparty, MockResponse};
+use crate::cosmos::{msg_create_client, CosmosSigner};
+use crate::relayer::daemon::Daemon;
+use crate::relayer::Relayer;
+use crate::tests::network::ethpkg::config::Preset;
+use crate::tests::network::{EthereumConfig, Readiness};
+use crate::tests::scenario::relayer::{deploy_ibc_handler, finalized_slot};
+use crate::tests::scenario::Scenario;
+
+pub struct RelayerDaemon;
+
+impl Scenario for RelayerDaemon {
+    fn readiness(&self) -> Readiness {
+        Readiness::FinalizedPeriod(2)
+    }
+
+    fn preset(&self) -> Option<Preset> {
+        Some(Preset::Minimal)
+    }
+
+    async fn run(&self, config: EthereumConfig) -> TestResult {
+        let EthereumConfig {
+            el_endpoint,
+            cl_endpoint,
+            el_proof_window,
+            archive_el_endpoint,
+            mnemonics,
+            ..
+        } = config;
+
+        let cl_endpoint = cl_endpoint.context("no cl_endpoint")?;
+
+        let beacon_client =
+            beacon_api::client::BeaconApiClient::new(cl_endpoint.to_string()).await?;
+
+        let spec = beacon_client.spec().await?.data;
+
+        let finalized_slot = finalized_slot(&cl_endpoint).await?;
+
+        let ibc_handler_address = deploy_ibc_handler(&el_endpoint, &mnemonics[0]).await?;
+
+        let relayer = Relayer::<Minimal>::builder()
+            .ibc_handler_address(ibc_handler_address)
+            .cl_endpoint(cl_endpoint)
+            .el_endpoint(el_endpoint)
+            .maybe_proof_window(el_proof_window)
+            .maybe_archive_el_endpoint(archive_el_endpoint)
+            .build();
+
+        let signer = CosmosSigner::from_mnemonic(&mnemonics[0], "union")?.address()?;
+
+        let (client_state, consensus_state, trusted_sync_committee) =
+            relayer.initialize(finalized_slot).await?;
+
+        let mut counterparty = MockCounterparty::<Minimal>::default();
+
+        let MockResponse::ClientCreated { client_id, .. } = counterparty.handle(
+            msg_create_client(client_state, consensus_state, vec![0; 32], signer.clone()),
+        )?
+        else {
+            return Err("expected client to be created".into());
+        };
+
+        let daemon = Daemon::builder()
+            .relayer(relayer)
+            .trusting_period(counterparty.trusting_period)
+            .counterparty(MockClientHandle {
+                counterparty,
+                client_id: client_id.clone(),
+                signer,
+            })
+            .trusted_sync_committee(trusted_sync_committee)
+            .max_lag(spec.slots_per_epoch)
+            .poll_interval(core::time::Duration::from_secs(spec.seconds_per_slot))
+            .build();
+
+        let cancel = CancellationToken::new();
+
+        let (daemon, ()) = tokio::join!(daemon.run(cancel.clone()), async {
+            tokio::time::sleep(core::time::Duration::from_secs(
+                spec.seconds_per_slot * spec.period() * 2,
+            ))
+            .await;
+            cancel.cancel();
+        });
+
+        let counterparty = &daemon.counterparty.counterparty;
+        let latest_height = counterparty.client(&client_id)?.latest_height();
+
+        tracing::info!(
+            from = finalized_slot,
+            to = latest_height,
+            "counterparty advanced"
+        );
+
+        assert!(latest_height > finalized_slot + spec.period());
+        assert_eq!(
+            latest_height,
+            daemon.trusted_sync_committee.trusted_height.revision_height
+        );
+        assert_eq!(counterparty.status(&client_id)?, ClientStatus::Active);
+
+        Ok(())
+    }
+}
@@ -38,13 +38,11 @@ pub struct ERC20Transfer;
 impl Scenario for ERC20Transfer {
     async fn run(&self, config: EthereumConfig) -> TestResult {
         let EthereumConfig {
-            el_socket,
+            el_endpoint,
             mnemonics,
             ..
         } = config;
 
-        let url = format!("http://{}", el_socket).to_string();
-
         let mnemonic = &mnemonics[0];
 
         let wallet = MnemonicBuilder::<English>::default()
@@ -56,8 +54,7 @@ impl Scenario for ERC20Transfer {
         let provider = ProviderBuilder::new()
             .with_recommended_fillers()
             .wallet(ethereum_wallet)
-            .on_builtin(&url)
-            .await?;
+            .on_client(el_endpoint.rpc_client().await?);
 
         let name = "MyToken".to_string();
         let symbol = "MTK".to_string();
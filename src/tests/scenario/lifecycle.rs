@@ -0,0 +1,153 @@
+use anyhow::Context;
+use testresult::TestResult;
+use unionlabs::ethereum::config::Minimal;
+use unionlabs::ibc::lightclients::ethereum::misbehaviour::Misbehaviour;
+
+use crate::cosmos::mock::{ClientStatus, MockCounterparty, MockResponse};
+use crate::cosmos::{msg_create_client, msg_submit_misbehaviour, msg_update_client, CosmosSigner};
+use crate::relayer::Relayer;
+use crate::tests::network::ethpkg::config::Preset;
+use crate::tests::network::{EthereumConfig, Readiness};
+use crate::tests::scenario::relayer::{deploy_ibc_handler, finalized_slot};
+use crate::tests::scenario::Scenario;
+
+pub struct ClientLifecycle;
+
+impl Scenario for ClientLifecycle {
+    fn readiness(&self) -> Readiness {
+        Readiness::FinalizedPeriod(2)
+    }
+
+    fn preset(&self) -> Option<Preset> {
+        Some(Preset::Minimal)
+    }
+
+    async fn run(&self, config: EthereumConfig) -> TestResult {
+        let EthereumConfig {
+            el_endpoint,
+            cl_endpoint,
+            el_proof_window,
+            archive_el_endpoint,
+            mnemonics,
+            ..
+        } = config;
+
+        let cl_endpoint = cl_endpoint.context("no cl_endpoint")?;
+
+        let beacon_client =
+            beacon_api::client::BeaconApiClient::new(cl_endpoint.to_string()).await?;
+
+        let spec = beacon_client.spec().await?.data;
+
+        let finalized_slot = finalized_slot(&cl_endpoint).await?;
+
+        let ibc_handler_address = deploy_ibc_handler(&el_endpoint, &mnemonics[0]).await?;
+
+        let relayer = Relayer::<Minimal>::builder()
+            .ibc_handler_address(ibc_handler_address)
+            .cl_endpoint(cl_endpoint)
+            .el_endpoint(el_endpoint)
+            .maybe_proof_window(el_proof_window)
+            .maybe_archive_el_endpoint(archive_el_endpoint)
+            .build();
+
+        let signer = CosmosSigner::from_mnemonic(&mnemonics[0], "union")?.address()?;
+
+        let mut counterparty = MockCounterparty::<Minimal>::default();
+
+        // CREATE
+        let (client_state, consensus_state, trusted_sync_committee) =
+            relayer.initialize(finalized_slot).await?;
+
+        let MockResponse::ClientCreated { client_id, height } = counterparty.handle(
+            msg_create_client(client_state, consensus_state, vec![0; 32], signer.clone()),
+        )?
+        else {
+            return Err("expected client to be created".into());
+        };
+
+        assert_eq!(height, finalized_slot);
+        assert_eq!(counterparty.status(&client_id)?, ClientStatus::Active);
+
+        tokio::time::sleep(core::time::Duration::from_secs(
+            spec.seconds_per_slot * spec.period() * 2,
+        ))
+        .await;
+
+        // UPDATE
+        let (headers, trusted_sync_committee) = relayer.header(trusted_sync_committee).await?;
+
+        tracing::info!(%client_id, headers = headers.len(), "updating client");
+
+        for header in &headers {
+            let response = counterparty.handle(msg_update_client(
+                client_id.clone(),
+                header.clone(),
+                signer.clone(),
+            ))?;
+
+            assert_eq!(
+                response,
+                MockResponse::ClientUpdated {
+                    client_id: client_id.clone(),
+                    height: header.consensus_update.finalized_header.beacon.slot,
+                }
+            );
+        }
+
+        assert_eq!(
+            counterparty.client(&client_id)?.latest_height(),
+            trusted_sync_committee.trusted_height.revision_height,
+        );
+
+        let first_header = headers.first().context("no headers")?.clone();
+        let last_header = headers.last().context("no headers")?.clone();
+
+        // heights must be monotonic
+        assert!(counterparty
+            .handle(msg_update_client(
+                client_id.clone(),
+                first_header,
+                signer.clone()
+            ))
+            .is_err());
+
+        // EXPIRY
+        let latest_timestamp = counterparty
+            .client(&client_id)?
+            .latest_consensus_state()
+            .context("latest consensus state")?
+            .timestamp;
+
+        counterparty.now =
+            Some(latest_timestamp + u64::try_from(counterparty.trusting_period.as_nanos())?);
+        assert_eq!(counterparty.status(&client_id)?, ClientStatus::Expired);
+
+        counterparty.now = None;
+        assert_eq!(counterparty.status(&client_id)?, ClientStatus::Active);
+
+        // MISBEHAVIOUR
+        let update_1 = last_header.consensus_update.clone();
+        let mut update_2 = last_header.consensus_update.clone();
+        update_2.finalized_header.execution.block_number += 1;
+
+        let response = counterparty.handle(msg_submit_misbehaviour(
+            client_id.clone(),
+            Misbehaviour {
+                trusted_sync_committee: last_header.trusted_sync_committee.clone(),
+                update_1,
+                update_2,
+            },
+            signer.clone(),
+        ))?;
+
+        assert!(matches!(response, MockResponse::ClientFrozen { .. }));
+        assert_eq!(counterparty.status(&client_id)?, ClientStatus::Frozen);
+
+        assert!(counterparty
+            .handle(msg_update_client(client_id.clone(), last_header, signer))
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -2,12 +2,32 @@ use core::future::Future;
 
 use testresult::TestResult;
 
-use crate::tests::network::EthereumConfig;
+use crate::tests::network::ethpkg::config::Preset;
+use crate::tests::network::{EthereumConfig, Readiness};
 
 pub mod beacon;
+pub mod checkpoint;
+pub mod daemon;
 pub mod erc20;
+pub mod lifecycle;
+pub mod optimistic;
 pub mod relayer;
+pub mod rpc;
+pub mod ssz;
+pub mod store;
+pub mod wasm;
 
 pub trait Scenario {
+    // test_beacon_e2e waits for the network to reach this before running
+    fn readiness(&self) -> Readiness {
+        Readiness::ElUp
+    }
+
+    // the preset the scenario relays with, test_beacon_e2e refuses networks running another
+    // one, none when any network will do
+    fn preset(&self) -> Option<Preset> {
+        None
+    }
+
     fn run(&self, config: EthereumConfig) -> impl Future<Output = TestResult> + Send;
 }
@@ -0,0 +1,208 @@
+use anyhow::Context;
+use futures::{StreamExt, TryStreamExt};
+use testresult::TestResult;
+use unionlabs::ethereum::config::Minimal;
+
+use crate::cosmos::mock::{MockClientHandle, MockCounterparty, MockResponse};
+use crate::cosmos::{msg_create_client, CosmosSigner};
+use crate::merkle;
+use crate::relayer::daemon::Counterparty;
+use crate::relayer::optimistic::RelayedHeader;
+use crate::relayer::Relayer;
+use crate::tests::network::ethpkg::config::Preset;
+use crate::tests::network::{EthereumConfig, Readiness};
+use crate::tests::scenario::relayer::{deploy_ibc_handler, finalized_slot};
+use crate::tests::scenario::Scenario;
+
+const OPTIMISTIC_UPDATES: usize = 8;
+
+pub struct OptimisticTracking;
+
+impl Scenario for OptimisticTracking {
+    fn readiness(&self) -> Readiness {
+        Readiness::FinalizedPeriod(1)
+    }
+
+    fn preset(&self) -> Option<Preset> {
+        Some(Preset::Minimal)
+    }
+
+    async fn run(&self, config: EthereumConfig) -> TestResult {
+        let EthereumConfig {
+            el_endpoint,
+            cl_endpoint,
+            el_proof_window,
+            archive_el_endpoint,
+            mnemonics,
+            ..
+        } = config;
+
+        let cl_endpoint = cl_endpoint.context("no cl_endpoint")?;
+
+        let beacon_client =
+            beacon_api::client::BeaconApiClient::new(cl_endpoint.to_string()).await?;
+
+        let spec = beacon_client.spec().await?.data;
+
+        let finalized_slot = finalized_slot(&cl_endpoint).await?;
+
+        let ibc_handler_address = deploy_ibc_handler(&el_endpoint, &mnemonics[0]).await?;
+
+        let relayer = Relayer::<Minimal>::builder()
+            .ibc_handler_address(ibc_handler_address)
+            .cl_endpoint(cl_endpoint)
+            .el_endpoint(el_endpoint)
+            .maybe_proof_window(el_proof_window)
+            .maybe_archive_el_endpoint(archive_el_endpoint)
+            .build();
+
+        let signer = CosmosSigner::from_mnemonic(&mnemonics[0], "union")?.address()?;
+
+        let (client_state, consensus_state, trusted_sync_committee) =
+            relayer.initialize(finalized_slot).await?;
+
+        let mut counterparty = MockCounterparty::<Minimal>::default();
+
+        let MockResponse::ClientCreated { client_id, .. } = counterparty.handle(
+            msg_create_client(client_state, consensus_state, vec![0; 32], signer.clone()),
+        )?
+        else {
+            return Err("expected client to be created".into());
+        };
+
+        let mut counterparty = MockClientHandle {
+            counterparty,
+            client_id: client_id.clone(),
+            signer,
+        };
+
+        let initial_height = counterparty
+            .counterparty
+            .client(&client_id)?
+            .latest_height();
+
+        let latest = relayer.optimistic_update().await?;
+
+        tracing::info!(slot = latest.slot(), "optimistic update over rest");
+
+        let updates = relayer
+            .optimistic_updates()
+            .await?
+            .take(OPTIMISTIC_UPDATES)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        assert_eq!(updates.len(), OPTIMISTIC_UPDATES);
+        assert!(updates.windows(2).all(|x| x[0].slot() <= x[1].slot()));
+
+        let mut headers = vec![];
+
+        for update in updates {
+            let finalized_slot = relayer
+                .finality_update()
+                .await?
+                .finalized_header
+                .beacon
+                .slot;
+
+            // how far ahead of finality optimistic tracking is
+            tracing::info!(
+                slot = update.slot(),
+                finalized_slot,
+                ahead = update.slot().saturating_sub(finalized_slot),
+                "optimistic update"
+            );
+
+            assert!(update.slot() > finalized_slot);
+
+            let header = relayer
+                .optimistic_header(trusted_sync_committee.clone(), update)
+                .await?;
+
+            assert!(!header.is_finalized());
+
+            counterparty.submit_relayed(header.clone()).await?;
+
+            let client = counterparty.counterparty.client(&client_id)?;
+
+            assert_eq!(
+                client.optimistic_heads.latest().map(|x| x.beacon.slot),
+                Some(header.slot())
+            );
+            // optimistic heads never move the finalized height
+            assert_eq!(client.latest_height(), initial_height);
+
+            headers.push(header);
+        }
+
+        let last_slot = headers
+            .iter()
+            .map(RelayedHeader::slot)
+            .max()
+            .context("no headers")?;
+
+        // wait for finality to pass every optimistic header, reorged ones included
+        while relayer
+            .finality_update()
+            .await?
+            .finalized_header
+            .beacon
+            .slot
+            < last_slot
+        {
+            tokio::time::sleep(core::time::Duration::from_secs(spec.seconds_per_slot)).await;
+        }
+
+        let (finalized_headers, _) = relayer.header(trusted_sync_committee).await?;
+
+        for header in finalized_headers {
+            let header = RelayedHeader::Finalized(header);
+
+            assert!(header.is_finalized());
+
+            counterparty.submit_relayed(header).await?;
+        }
+
+        let client = counterparty.counterparty.client(&client_id)?;
+
+        assert!(client.latest_height() >= last_slot);
+        // finality superseded every optimistic head
+        assert!(client.optimistic_heads.is_empty());
+
+        // optimistic headers can be rolled back, check which ones are still canonical
+        let mut reorged = 0;
+
+        for header in &headers {
+            let RelayedHeader::Optimistic(optimistic_header) = header else {
+                continue;
+            };
+
+            let attested = &optimistic_header.optimistic_update.attested_header.beacon;
+
+            let root = merkle::beacon_block_header_root(
+                attested.slot,
+                attested.proposer_index,
+                attested.parent_root,
+                attested.state_root,
+                attested.body_root,
+            )?;
+
+            let canonical = beacon_client
+                .header(attested.slot.into())
+                .await
+                .is_ok_and(|x| merkle::bytes32(x.data.root).is_ok_and(|x| x == root));
+
+            if !canonical {
+                reorged += 1;
+                tracing::warn!(slot = attested.slot, "optimistic header was reorged out");
+            }
+
+            // at or below the finalized height now, reorged or not
+            assert!(counterparty.submit_relayed(header.clone()).await.is_err());
+        }
+
+        tracing::info!(reorged, headers = headers.len(), "optimistic tracking done");
+
+        Ok(())
+    }
+}
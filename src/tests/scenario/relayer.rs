@@ -1,138 +1,161 @@
 use alloy::network::EthereumWallet;
-use alloy::primitives::U256;
-use alloy::providers::ProviderBuilder;
-use alloy::transports::http::reqwest;
+use alloy::primitives::{keccak256, Address, B256};
+use alloy::providers::{Provider, ProviderBuilder};
 use alloy_signer_local::coins_bip39::English;
 use alloy_signer_local::MnemonicBuilder;
+use alloy_sol_types::sol;
 use anyhow::Context;
-use futures::TryStreamExt;
+use ics008_wasm_client::MerklePath;
 use testresult::TestResult;
 use unionlabs::ethereum::config::Minimal;
 
-use crate::relayer::Relayer;
-use crate::tests::network::EthereumConfig;
-use crate::tests::scenario::erc20::Erc20;
+use crate::endpoint::Endpoint;
+use crate::relayer::{commitment_key, Relayer};
+use crate::tests::network::ethpkg::config::Preset;
+use crate::tests::network::{EthereumConfig, Readiness};
 use crate::tests::scenario::Scenario;
 
+sol!(
+    #[sol(rpc)]
+    #[derive(Debug)]
+    Commitments,
+    "out/commitments.sol/Commitments.json",
+);
+
+// the light client finality update can trail the first finalized checkpoint by a few slots
+pub async fn finalized_slot(cl_endpoint: &Endpoint) -> TestResult<u64> {
+    let beacon_client = beacon_api::client::BeaconApiClient::new(cl_endpoint.to_string()).await?;
+
+    let mut attempts = 0;
+
+    loop {
+        match beacon_client.finality_update().await {
+            Ok(finality_update) => {
+                let slot = finality_update.data.finalized_header.beacon.slot;
+
+                tracing::info!(slot, "finalized slot");
+
+                return Ok(slot);
+            }
+            Err(err) if attempts < 60 => {
+                tracing::debug!(?err, "no finality update yet");
+                attempts += 1;
+                tokio::time::sleep(core::time::Duration::from_secs(1)).await;
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+async fn wallet_provider(el_endpoint: &Endpoint, mnemonic: &str) -> TestResult<impl Provider> {
+    let wallet = MnemonicBuilder::<English>::default()
+        .phrase(mnemonic)
+        .build()?;
+
+    let ethereum_wallet = EthereumWallet::new(wallet);
+
+    Ok(ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(ethereum_wallet)
+        .on_client(el_endpoint.rpc_client().await?))
+}
+
+pub async fn deploy_ibc_handler(el_endpoint: &Endpoint, mnemonic: &str) -> TestResult<Address> {
+    let provider = wallet_provider(el_endpoint, mnemonic).await?;
+
+    // TODO(rano): deploy the actual IBC contract
+    let contract = Commitments::deploy(&provider).await?;
+
+    Ok(*contract.address())
+}
+
+// stores keccak256(value) under the path, as the handler does for a packet commitment
+pub async fn commit(
+    el_endpoint: &Endpoint,
+    mnemonic: &str,
+    ibc_handler_address: Address,
+    path: &MerklePath,
+    value: &[u8],
+) -> TestResult {
+    let provider = wallet_provider(el_endpoint, mnemonic).await?;
+
+    let contract = Commitments::new(ibc_handler_address, &provider);
+
+    let key = B256::from(commitment_key(path)?.to_be_bytes());
+
+    let receipt = contract
+        .commit(key, keccak256(value))
+        .send()
+        .await?
+        .get_receipt()
+        .await?;
+
+    if !receipt.status() {
+        return Err(format!("committing {:?} reverted", path.key_path).into());
+    }
+
+    assert_eq!(
+        contract.commitments(key).call().await?.commitment,
+        keccak256(value)
+    );
+
+    Ok(())
+}
+
 pub struct RelayerMsg;
 
 impl Scenario for RelayerMsg {
+    fn readiness(&self) -> Readiness {
+        Readiness::FinalizedPeriod(2)
+    }
+
+    fn preset(&self) -> Option<Preset> {
+        Some(Preset::Minimal)
+    }
+
     async fn run(&self, config: EthereumConfig) -> TestResult {
         let EthereumConfig {
-            el_socket,
-            cl_socket,
+            el_endpoint,
+            cl_endpoint,
+            el_proof_window,
+            archive_el_endpoint,
             mnemonics,
             ..
         } = config;
 
-        let cl_socket = cl_socket.context("no cl_socket")?;
+        let cl_endpoint = cl_endpoint.context("no cl_endpoint")?;
 
         let beacon_client =
-            beacon_api::client::BeaconApiClient::new(format!("http://{}", cl_socket)).await?;
+            beacon_api::client::BeaconApiClient::new(cl_endpoint.to_string()).await?;
 
         let spec = beacon_client.spec().await?.data;
-        println!("{}", serde_json::to_string_pretty(&spec)?);
-
-        let finalized_header = match beacon_client.finality_update().await {
-            Ok(finality_update) => finality_update.data.finalized_header,
-            Err(_) => {
-                let mut stream = reqwest::Client::new()
-                    .get(format!("http://{}/eth/v1/events", cl_socket))
-                    .query(&[("topics", "light_client_finality_update")])
-                    .send()
-                    .await?
-                    .bytes_stream();
-
-                loop {
-                    if let Some(event) = stream.try_next().await? {
-                        if event.starts_with(b"event: light_client_finality_update\n") {
-                            break;
-                        }
-                    }
-                }
-                beacon_client.finality_update().await?.data.finalized_header
-            }
-        };
 
-        {
-            // current period should be at least 2
+        // current period should be at least 2
+        let finalized_slot = finalized_slot(&cl_endpoint).await?;
 
-            let current_period = finalized_header.beacon.slot / spec.period();
+        let ibc_handler_address = deploy_ibc_handler(&el_endpoint, &mnemonics[0]).await?;
 
-            println!("current period: {}", current_period);
+        tracing::info!(%ibc_handler_address, "deployed ibc handler");
 
-            if current_period < 2 {
-                tokio::time::sleep(core::time::Duration::from_secs(
-                    spec.seconds_per_slot * spec.period() * (2 - current_period),
-                ))
-                .await;
-            }
-        }
+        let relayer = Relayer::<Minimal>::builder()
+            .ibc_handler_address(ibc_handler_address)
+            .cl_endpoint(cl_endpoint)
+            .el_endpoint(el_endpoint)
+            .maybe_proof_window(el_proof_window)
+            .maybe_archive_el_endpoint(archive_el_endpoint)
+            .build();
 
-        let ibc_handler_address = {
-            let url = format!("http://{}", el_socket).to_string();
-
-            let mnemonic = &mnemonics[0];
-
-            let wallet = MnemonicBuilder::<English>::default()
-                .phrase(mnemonic)
-                .build()?;
-
-            let ethereum_wallet = EthereumWallet::new(wallet);
-
-            let provider = ProviderBuilder::new()
-                .with_recommended_fillers()
-                .wallet(ethereum_wallet)
-                .on_builtin(&url)
-                .await?;
-
-            let name = "MyToken".to_string();
-            let symbol = "MTK".to_string();
-            let decimals = 18u8;
-            let total_supply = U256::from(1_000_000);
-
-            // TODO(rano): deploy the actual IBC contract
-            let contract = Erc20::deploy(
-                &provider,
-                name.clone(),
-                symbol.clone(),
-                decimals,
-                total_supply,
-            )
-            .await?;
-
-            *contract.address()
-        };
-
-        println!("IBC Handler: {}", ibc_handler_address);
-
-        let relayer = Relayer::<Minimal> {
-            ibc_handler_address,
-            cl_socket,
-            el_socket,
-            _phantom: Default::default(),
-        };
-
-        println!(
-            "building initialize state at slot {}",
-            finalized_header.beacon.slot - 1
-        );
+        tracing::info!(slot = finalized_slot, "building initialize state");
 
         // initialize the relayer at a finalized header
         let (client_state, consensus_state, trusted_sync_committee) =
-            relayer.initialize(finalized_header.beacon.slot).await?;
+            relayer.initialize(finalized_slot).await?;
 
-        println!(
-            "ClientState: {}",
-            serde_json::to_string_pretty(&client_state)?
-        );
-        println!(
-            "ConsensusState: {}",
-            serde_json::to_string_pretty(&consensus_state)?
-        );
-        println!(
-            "TrustedSyncCommittee: {}",
-            serde_json::to_string_pretty(&trusted_sync_committee)?
+        tracing::info!(
+            client_state = %serde_json::to_string(&client_state)?,
+            consensus_state = %serde_json::to_string(&consensus_state)?,
+            trusted_sync_committee = %serde_json::to_string(&trusted_sync_committee)?,
+            "initialized",
         );
 
         tokio::time::sleep(core::time::Duration::from_secs(
@@ -142,10 +165,10 @@ impl Scenario for RelayerMsg {
 
         let (headers, trusted_sync_committee) = relayer.header(trusted_sync_committee).await?;
 
-        println!("Headers: {}", serde_json::to_string_pretty(&headers)?);
-        println!(
-            "TrustedSyncCommittee: {}",
-            serde_json::to_string_pretty(&trusted_sync_committee)?
+        tracing::info!(
+            headers = %serde_json::to_string(&headers)?,
+            trusted_sync_committee = %serde_json::to_string(&trusted_sync_committee)?,
+            "updated",
         );
 
         Ok(())
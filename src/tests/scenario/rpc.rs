@@ -0,0 +1,165 @@
+use core::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::Context;
+use serde_json::{json, Value};
+use testresult::TestResult;
+use tokio_util::sync::CancellationToken;
+use unionlabs::ethereum::config::Minimal;
+
+use crate::relayer::metrics::Metrics;
+use crate::relayer::rpc::{RpcResponse, RpcServer, METHOD_NOT_FOUND};
+use crate::relayer::Relayer;
+use crate::tests::network::ethpkg::config::Preset;
+use crate::tests::network::{EthereumConfig, Readiness};
+use crate::tests::scenario::relayer::{deploy_ibc_handler, finalized_slot};
+use crate::tests::scenario::Scenario;
+
+async fn call(rpc: SocketAddr, id: u64, method: &str, params: Value) -> TestResult<RpcResponse> {
+    Ok(reqwest::Client::new()
+        .post(format!("http://{}", rpc))
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        }))
+        .send()
+        .await?
+        .json()
+        .await?)
+}
+
+fn result(response: RpcResponse) -> TestResult<Value> {
+    if let Some(error) = response.error {
+        return Err(format!("rpc error {}: {}", error.code, error.message).into());
+    }
+
+    Ok(response.result.context("no result")?)
+}
+
+pub struct RelayerRpc;
+
+impl Scenario for RelayerRpc {
+    fn readiness(&self) -> Readiness {
+        Readiness::FinalizedPeriod(1)
+    }
+
+    fn preset(&self) -> Option<Preset> {
+        Some(Preset::Minimal)
+    }
+
+    async fn run(&self, config: EthereumConfig) -> TestResult {
+        let EthereumConfig {
+            el_endpoint,
+            cl_endpoint,
+            el_proof_window,
+            archive_el_endpoint,
+            mnemonics,
+            ..
+        } = config;
+
+        let cl_endpoint = cl_endpoint.context("no cl_endpoint")?;
+
+        let beacon_client =
+            beacon_api::client::BeaconApiClient::new(cl_endpoint.to_string()).await?;
+
+        let spec = beacon_client.spec().await?.data;
+
+        let finalized_slot = finalized_slot(&cl_endpoint).await?;
+
+        let ibc_handler_address = deploy_ibc_handler(&el_endpoint, &mnemonics[0]).await?;
+
+        let relayer = Relayer::<Minimal>::builder()
+            .ibc_handler_address(ibc_handler_address)
+            .cl_endpoint(cl_endpoint)
+            .el_endpoint(el_endpoint)
+            .maybe_proof_window(el_proof_window)
+            .maybe_archive_el_endpoint(archive_el_endpoint)
+            .metrics(Arc::new(Metrics::new()?))
+            .build();
+
+        let cancel = CancellationToken::new();
+
+        let (rpc, handle) = RpcServer::new(relayer)
+            .spawn("127.0.0.1:0".parse()?, cancel.clone())
+            .await?;
+
+        let health = reqwest::get(format!("http://{}/health", rpc)).await?;
+        assert!(health.status().is_success());
+
+        // concurrent requests share the server's clients
+        let (initialize, json_proof, proto_proof, unknown) = tokio::join!(
+            call(rpc, 1, "initialize", json!({ "slot": finalized_slot })),
+            call(
+                rpc,
+                2,
+                "account_proof",
+                json!({ "slot": finalized_slot, "paths": [["commitments/ports/transfer"]] }),
+            ),
+            call(
+                rpc,
+                3,
+                "account_proof",
+                json!({ "slot": finalized_slot, "encoding": "proto" }),
+            ),
+            call(rpc, 4, "nope", Value::Null),
+        );
+
+        let initialize = result(initialize?)?;
+
+        assert_eq!(
+            initialize["client_state"]["latest_slot"].as_u64(),
+            Some(finalized_slot)
+        );
+        assert_eq!(
+            result(json_proof?)?["storage_proofs"]
+                .as_array()
+                .map(Vec::len),
+            Some(1)
+        );
+        assert!(result(proto_proof?)?["account_proof"]
+            .as_str()
+            .is_some_and(|x| x.starts_with("0x")));
+        assert_eq!(unknown?.error.map(|x| x.code), Some(METHOD_NOT_FOUND));
+
+        tokio::time::sleep(core::time::Duration::from_secs(
+            spec.seconds_per_slot * spec.slots_per_epoch * 3,
+        ))
+        .await;
+
+        let header = result(
+            call(
+                rpc,
+                5,
+                "header",
+                json!({
+                    "trusted_sync_committee": initialize["trusted_sync_committee"],
+                    "encoding": "proto",
+                }),
+            )
+            .await?,
+        )?;
+
+        assert!(header["headers"]
+            .as_array()
+            .is_some_and(|headers| !headers.is_empty()));
+
+        let metrics = reqwest::get(format!("http://{}/metrics", rpc))
+            .await?
+            .text()
+            .await?;
+
+        assert!(metrics.contains("relayer_headers_built_total"));
+        assert!(metrics.contains("relayer_rpc_duration_seconds_count{method=\"header\"} 1"));
+        assert!(metrics.contains("endpoint=\"eth_getProof\""));
+        // calls through union's beacon client are timed too
+        assert!(metrics.contains("endpoint=\"/eth/v1/config/spec\""));
+        assert!(metrics.contains("endpoint=\"/eth/v1/beacon/headers/:id\""));
+
+        cancel.cancel();
+        handle.await??;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::Context;
+use testresult::TestResult;
+use unionlabs::ethereum::config::Minimal;
+
+use crate::relayer::Relayer;
+use crate::tests::network::ethpkg::config::Preset;
+use crate::tests::network::{EthereumConfig, Readiness};
+use crate::tests::scenario::relayer::finalized_slot;
+use crate::tests::scenario::Scenario;
+
+pub struct SszTransport;
+
+impl Scenario for SszTransport {
+    fn readiness(&self) -> Readiness {
+        Readiness::FinalizedPeriod(1)
+    }
+
+    fn preset(&self) -> Option<Preset> {
+        Some(Preset::Minimal)
+    }
+
+    async fn run(&self, config: EthereumConfig) -> TestResult {
+        let EthereumConfig {
+            el_endpoint,
+            cl_endpoint,
+            ..
+        } = config;
+
+        let cl_endpoint = cl_endpoint.context("no cl_endpoint")?;
+
+        let beacon_client =
+            beacon_api::client::BeaconApiClient::new(cl_endpoint.to_string()).await?;
+
+        let spec = beacon_client.spec().await?.data;
+
+        let finalized_slot = finalized_slot(&cl_endpoint).await?;
+
+        // the handler is not needed to fetch light client data
+        let relayer = Relayer::<Minimal>::builder()
+            .ibc_handler_address(Default::default())
+            .cl_endpoint(cl_endpoint)
+            .el_endpoint(el_endpoint)
+            .build();
+
+        // the *_ssz calls do not fall back, so a CL without ssz support fails here
+        let updates = relayer
+            .light_client_updates_ssz(0, finalized_slot / spec.period() + 1)
+            .await?;
+
+        let json_updates = beacon_client
+            .light_client_updates(0, finalized_slot / spec.period() + 1)
+            .await?
+            .0
+            .into_iter()
+            .map(|x| x.data)
+            .collect::<Vec<_>>();
+
+        assert!(!updates.is_empty());
+        assert_eq!(
+            serde_json::to_value(&updates)?,
+            serde_json::to_value(&json_updates)?
+        );
+
+        let finalized_root = beacon_client.header(finalized_slot.into()).await?.data.root;
+
+        let bootstrap = relayer.bootstrap_ssz(finalized_root).await?;
+        let json_bootstrap = beacon_client.bootstrap(finalized_root).await?.data;
+
+        assert_eq!(
+            serde_json::to_value(&bootstrap)?,
+            serde_json::to_value(&json_bootstrap)?
+        );
+
+        let finality_update = relayer.finality_update_ssz().await?;
+        let json_finality_update = beacon_client.finality_update().await?.data;
+
+        // finality may advance between the two requests
+        if finality_update.finalized_header.beacon.slot
+            == json_finality_update.finalized_header.beacon.slot
+        {
+            assert_eq!(
+                serde_json::to_value(&finality_update)?,
+                serde_json::to_value(&json_finality_update)?
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use std::time::SystemTime;
+
+use anyhow::Context;
+use testresult::TestResult;
+use unionlabs::ethereum::config::Minimal;
+
+use crate::light_client::LightClientStore;
+use crate::merkle;
+use crate::relayer::Relayer;
+use crate::tests::network::ethpkg::config::Preset;
+use crate::tests::network::{EthereumConfig, Readiness};
+use crate::tests::scenario::relayer::{deploy_ibc_handler, finalized_slot};
+use crate::tests::scenario::Scenario;
+
+pub struct LightClientSync;
+
+impl Scenario for LightClientSync {
+    fn readiness(&self) -> Readiness {
+        Readiness::FinalizedPeriod(1)
+    }
+
+    fn preset(&self) -> Option<Preset> {
+        Some(Preset::Minimal)
+    }
+
+    async fn run(&self, config: EthereumConfig) -> TestResult {
+        let EthereumConfig {
+            el_endpoint,
+            cl_endpoint,
+            el_proof_window,
+            archive_el_endpoint,
+            mnemonics,
+            ..
+        } = config;
+
+        let cl_endpoint = cl_endpoint.context("no cl_endpoint")?;
+
+        let beacon_client =
+            beacon_api::client::BeaconApiClient::new(cl_endpoint.to_string()).await?;
+
+        let spec = beacon_client.spec().await?.data;
+
+        let finalized_slot = finalized_slot(&cl_endpoint).await?;
+
+        let ibc_handler_address = deploy_ibc_handler(&el_endpoint, &mnemonics[0]).await?;
+
+        let relayer = Relayer::<Minimal>::builder()
+            .ibc_handler_address(ibc_handler_address)
+            .cl_endpoint(cl_endpoint)
+            .el_endpoint(el_endpoint)
+            .maybe_proof_window(el_proof_window)
+            .maybe_archive_el_endpoint(archive_el_endpoint)
+            .build();
+
+        let (checkpoint_slot, checkpoint_root) = relayer.checkpoint(finalized_slot).await?;
+
+        let mut store = LightClientStore::initialize(
+            relayer.light_client_config().await?,
+            merkle::bytes32(checkpoint_root)?,
+            relayer.bootstrap(checkpoint_root).await?,
+        )?;
+
+        // let the chain move into the next period so the sequence rotates committees
+        tokio::time::sleep(core::time::Duration::from_secs(
+            spec.seconds_per_slot * spec.period(),
+        ))
+        .await;
+
+        let updates = relayer.update_sequence(checkpoint_slot).await?;
+
+        assert!(!updates.is_empty());
+
+        for update in updates {
+            let current_slot = store.config.current_slot(
+                SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)?
+                    .as_secs(),
+            );
+
+            let finalized_slot = update.finalized_header.beacon.slot;
+
+            tracing::info!(
+                finalized_slot,
+                store_slot = store.finalized_header.beacon.slot,
+                "processing update"
+            );
+
+            store.process_light_client_update(update, current_slot)?;
+
+            // every update the relayer picks must advance a spec light client
+            assert_eq!(store.finalized_header.beacon.slot, finalized_slot);
+            assert!(store.best_valid_update.is_none());
+        }
+
+        assert!(store.optimistic_header.beacon.slot >= store.finalized_header.beacon.slot);
+
+        Ok(())
+    }
+}
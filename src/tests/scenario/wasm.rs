@@ -0,0 +1,121 @@
+use anyhow::Context;
+use ics008_wasm_client::MerklePath;
+use testresult::TestResult;
+use unionlabs::ethereum::config::Minimal;
+
+use crate::cosmos::wasm::WasmLightClient;
+use crate::relayer::Relayer;
+use crate::tests::network::ethpkg::config::Preset;
+use crate::tests::network::{EthereumConfig, Readiness};
+use crate::tests::scenario::relayer::{commit, deploy_ibc_handler, finalized_slot};
+use crate::tests::scenario::Scenario;
+
+pub struct WasmClientExec;
+
+impl Scenario for WasmClientExec {
+    fn readiness(&self) -> Readiness {
+        Readiness::FinalizedPeriod(2)
+    }
+
+    fn preset(&self) -> Option<Preset> {
+        Some(Preset::Minimal)
+    }
+
+    async fn run(&self, config: EthereumConfig) -> TestResult {
+        let EthereumConfig {
+            el_endpoint,
+            cl_endpoint,
+            el_proof_window,
+            archive_el_endpoint,
+            mnemonics,
+            ..
+        } = config;
+
+        // fail before waiting on the network when the blob was never built
+        let wasm_path = WasmLightClient::default_path()?;
+
+        let cl_endpoint = cl_endpoint.context("no cl_endpoint")?;
+
+        let beacon_client =
+            beacon_api::client::BeaconApiClient::new(cl_endpoint.to_string()).await?;
+
+        let spec = beacon_client.spec().await?.data;
+
+        let finalized_slot = finalized_slot(&cl_endpoint).await?;
+
+        let ibc_handler_address = deploy_ibc_handler(&el_endpoint, &mnemonics[0]).await?;
+
+        let committed_path = MerklePath {
+            key_path: vec!["commitments/ports/transfer/channels/channel-0/sequences/1".to_string()],
+        };
+        let committed_value = b"packet commitment".to_vec();
+
+        // included before the slots the light client is updated to
+        commit(
+            &el_endpoint,
+            &mnemonics[0],
+            ibc_handler_address,
+            &committed_path,
+            &committed_value,
+        )
+        .await?;
+
+        let relayer = Relayer::<Minimal>::builder()
+            .ibc_handler_address(ibc_handler_address)
+            .cl_endpoint(cl_endpoint)
+            .el_endpoint(el_endpoint)
+            .maybe_proof_window(el_proof_window)
+            .maybe_archive_el_endpoint(archive_el_endpoint)
+            .build();
+
+        let (client_state, consensus_state, trusted_sync_committee) =
+            relayer.initialize(finalized_slot).await?;
+
+        tokio::time::sleep(core::time::Duration::from_secs(
+            spec.seconds_per_slot * spec.period() * 2,
+        ))
+        .await;
+
+        let (headers, trusted_sync_committee) = relayer.header(trusted_sync_committee).await?;
+
+        let latest_slot = trusted_sync_committee.trusted_height.revision_height;
+
+        let membership = relayer
+            .membership_proof(latest_slot, committed_path, committed_value)
+            .await?;
+
+        // nothing is committed at the next sequence
+        let path = MerklePath {
+            key_path: vec!["commitments/ports/transfer/channels/channel-0/sequences/2".to_string()],
+        };
+
+        let proof = relayer.non_membership_proof(latest_slot, path).await?;
+
+        // the vm instance is not Send, so it lives only after the last await
+        let mut light_client = WasmLightClient::load(wasm_path)?;
+
+        light_client.instantiate(client_state, consensus_state)?;
+        assert_eq!(light_client.status()?, "Active");
+
+        tracing::info!(headers = headers.len(), "updating light client");
+
+        for header in headers {
+            light_client.update(header)?;
+        }
+
+        assert_eq!(light_client.status()?, "Active");
+
+        // the light client derives the storage key from the path itself, so this also checks
+        // commitment_key against union's verification
+        light_client.verify_membership(
+            membership.slot,
+            membership.proof,
+            membership.path,
+            membership.value,
+        )?;
+
+        light_client.verify_non_membership(proof.slot, proof.proof, proof.path)?;
+
+        Ok(())
+    }
+}
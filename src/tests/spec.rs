@@ -0,0 +1,298 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use protos::union::ibc::lightclients::ethereum::v1::LightClientUpdate as LightClientUpdateProto;
+use serde_json::{json, Value};
+use testresult::TestResult;
+use unionlabs::ethereum::beacon::light_client_bootstrap::UnboundedLightClientBootstrap;
+use unionlabs::ethereum::config::Minimal;
+use unionlabs::ibc::lightclients::ethereum::light_client_header::UnboundedLightClientHeader;
+use unionlabs::ibc::lightclients::ethereum::light_client_update::{
+    LightClientUpdate, UnboundedLightClientUpdate,
+};
+
+use crate::light_client::{
+    beacon_root, execution_payload_header_root, LightClientConfig, LightClientStore,
+};
+use crate::merkle;
+use crate::relayer::ssz::{self, Fork, ForkDigest};
+use crate::tests::logging;
+
+// `just spec-tests` vendors the minimal light client sync vectors here
+pub const SPEC_TESTS_ENV: &str = "CONSENSUS_SPEC_TESTS";
+pub const SPEC_TESTS_DIR: &str = "spec-tests/tests/minimal";
+
+const SYNC_COMMITTEE_SIZE: usize = 32;
+
+// the store needs an execution header, so only capella onwards is replayed
+const FORKS: [Fork; 3] = [Fork::Capella, Fork::Deneb, Fork::Electra];
+
+fn read_yaml(path: impl AsRef<Path>) -> anyhow::Result<Value> {
+    let path = path.as_ref();
+    let yaml = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    Ok(serde_yaml::from_str(&yaml)?)
+}
+
+fn read_ssz_snappy(path: impl AsRef<Path>) -> anyhow::Result<Vec<u8>> {
+    let path = path.as_ref();
+    let compressed =
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+
+    Ok(snap::raw::Decoder::new().decompress_vec(&compressed)?)
+}
+
+fn hex_bytes(value: &Value) -> anyhow::Result<Vec<u8>> {
+    Ok(hex::decode(
+        value
+            .as_str()
+            .context("expected a hex string")?
+            .trim_start_matches("0x"),
+    )?)
+}
+
+fn as_u64(value: &Value) -> anyhow::Result<u64> {
+    match value {
+        Value::String(value) => Ok(value.parse()?),
+        value => value.as_u64().context("expected a uint64"),
+    }
+}
+
+// the minimal preset with every fork up to `fork` active from genesis
+fn default_config(fork: Fork) -> Value {
+    let mut config = json!({
+        "GENESIS_FORK_VERSION": "0x00000001",
+        "SECONDS_PER_SLOT": "6",
+        "SLOTS_PER_EPOCH": "8",
+        "EPOCHS_PER_SYNC_COMMITTEE_PERIOD": "8",
+        "MIN_SYNC_COMMITTEE_PARTICIPANTS": "1",
+    });
+
+    for (i, scheduled) in Fork::ALL.into_iter().enumerate() {
+        let prefix = scheduled.name().to_uppercase();
+
+        config[format!("{}_FORK_VERSION", prefix)] = json!(format!("0x0{}000001", i + 1));
+        config[format!("{}_FORK_EPOCH", prefix)] =
+            json!(if scheduled <= fork { 0 } else { u64::MAX });
+    }
+
+    config
+}
+
+fn fork_digests(config: &LightClientConfig) -> Vec<(ForkDigest, Fork)> {
+    config
+        .forks
+        .iter()
+        .filter_map(|schedule| {
+            schedule.fork.map(|fork| {
+                (
+                    ssz::fork_digest(schedule.version, config.genesis_validators_root),
+                    fork,
+                )
+            })
+        })
+        .collect()
+}
+
+fn fork_of(digests: &[(ForkDigest, Fork)], digest: &Value, default: Fork) -> anyhow::Result<Fork> {
+    if digest.is_null() {
+        return Ok(default);
+    }
+
+    let digest = hex_bytes(digest)?;
+
+    digests
+        .iter()
+        .find(|(fork_digest, _)| fork_digest[..] == digest[..])
+        .map(|(_, fork)| *fork)
+        .with_context(|| format!("unknown fork digest {}", hex::encode(digest)))
+}
+
+fn check_header(
+    config: &LightClientConfig,
+    name: &str,
+    header: &UnboundedLightClientHeader,
+    expected: &Value,
+) -> anyhow::Result<()> {
+    if expected.is_null() {
+        return Ok(());
+    }
+
+    anyhow::ensure!(
+        header.beacon.slot == as_u64(&expected["slot"])?,
+        "{} is at slot {} but {} was expected",
+        name,
+        header.beacon.slot,
+        expected["slot"],
+    );
+    anyhow::ensure!(
+        beacon_root(header)?[..] == hex_bytes(&expected["beacon_root"])?[..],
+        "{} beacon root mismatch at slot {}",
+        name,
+        header.beacon.slot,
+    );
+
+    if !expected["execution_root"].is_null() {
+        if let Some(fork) = config
+            .fork_at_slot(header.beacon.slot)?
+            .filter(|fork| *fork >= Fork::Capella)
+        {
+            anyhow::ensure!(
+                execution_payload_header_root(&serde_json::to_value(&header.execution)?, fork)?[..]
+                    == hex_bytes(&expected["execution_root"])?[..],
+                "{} execution root mismatch at slot {}",
+                name,
+                header.beacon.slot,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn check_store(store: &LightClientStore, checks: &Value) -> anyhow::Result<()> {
+    check_header(
+        &store.config,
+        "finalized header",
+        &store.finalized_header,
+        &checks["finalized_header"],
+    )?;
+    check_header(
+        &store.config,
+        "optimistic header",
+        &store.optimistic_header,
+        &checks["optimistic_header"],
+    )
+}
+
+fn run_case(case: &Path, fork: Fork) -> anyhow::Result<()> {
+    let meta = read_yaml(case.join("meta.yaml"))?;
+
+    let spec = if case.join("config.yaml").exists() {
+        read_yaml(case.join("config.yaml"))?
+    } else {
+        default_config(fork)
+    };
+
+    let genesis = json!({
+        "genesis_validators_root": meta["genesis_validators_root"],
+        "genesis_time": "0",
+    });
+
+    let config = LightClientConfig::from_spec(&spec, &genesis)?;
+    let digests = fork_digests(&config);
+
+    let bootstrap: UnboundedLightClientBootstrap =
+        serde_json::from_value(ssz::light_client_bootstrap(
+            &read_ssz_snappy(case.join("bootstrap.ssz_snappy"))?,
+            fork_of(&digests, &meta["bootstrap_fork_digest"], fork)?,
+            SYNC_COMMITTEE_SIZE,
+        )?)?;
+
+    let mut store = LightClientStore::initialize(
+        config,
+        merkle::bytes32(hex_bytes(&meta["trusted_block_root"])?)?,
+        bootstrap,
+    )?;
+
+    let steps = read_yaml(case.join("steps.yaml"))?;
+
+    for (i, step) in steps
+        .as_array()
+        .context("steps is not a list")?
+        .iter()
+        .enumerate()
+    {
+        let checks = if let Some(step) = step.get("process_update") {
+            let update: UnboundedLightClientUpdate =
+                serde_json::from_value(ssz::light_client_update(
+                    &read_ssz_snappy(case.join(format!(
+                        "{}.ssz_snappy",
+                        step["update"].as_str().context("update")?
+                    )))?,
+                    fork_of(&digests, &step["update_fork_digest"], fork)?,
+                    SYNC_COMMITTEE_SIZE,
+                )?)?;
+
+            // the relayer relays every update through this conversion
+            let bounded: LightClientUpdate<Minimal> =
+                LightClientUpdateProto::from(update.clone()).try_into()?;
+
+            anyhow::ensure!(
+                bounded.finalized_header.beacon.slot == update.finalized_header.beacon.slot
+            );
+            anyhow::ensure!(bounded.signature_slot == update.signature_slot);
+
+            store
+                .process_light_client_update(update, as_u64(&step["current_slot"])?)
+                .with_context(|| format!("step {}", i))?;
+
+            &step["checks"]
+        } else if let Some(step) = step.get("force_update") {
+            store.force_update(as_u64(&step["current_slot"])?)?;
+
+            &step["checks"]
+        } else if let Some(step) = step.get("upgrade_store") {
+            // the store holds json shaped headers, which need no upgrade
+            &step["checks"]
+        } else {
+            anyhow::bail!("unknown step {}", step);
+        };
+
+        check_store(&store, checks).with_context(|| format!("step {}", i))?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_light_client_sync_vectors() -> TestResult {
+    let _guard = logging::init()?;
+
+    let root = std::env::var(SPEC_TESTS_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(SPEC_TESTS_DIR));
+
+    if !root.exists() {
+        return Err(format!(
+            "no spec tests in {}, run `just spec-tests` to vendor them or set {}",
+            root.display(),
+            SPEC_TESTS_ENV
+        )
+        .into());
+    }
+
+    let mut cases = 0;
+
+    for fork in FORKS {
+        let dir = root
+            .join(fork.name())
+            .join("light_client/sync/pyspec_tests");
+
+        if !dir.exists() {
+            continue;
+        }
+
+        let mut paths = std::fs::read_dir(&dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        paths.sort();
+
+        for case in paths {
+            tracing::info!(case = %case.display(), "running spec test");
+
+            run_case(&case, fork).with_context(|| case.display().to_string())?;
+
+            cases += 1;
+        }
+    }
+
+    assert!(
+        cases > 0,
+        "no light client sync vectors in {}",
+        root.display()
+    );
+
+    Ok(())
+}
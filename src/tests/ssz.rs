@@ -0,0 +1,149 @@
+use testresult::TestResult;
+
+use crate::relayer::ssz::{fork_digest, light_client_bootstrap, light_client_update_chunks, Fork};
+
+const SYNC_COMMITTEE_SIZE: usize = 32;
+
+pub fn beacon_block_header(slot: u64) -> Vec<u8> {
+    [
+        slot.to_le_bytes().to_vec(),
+        7u64.to_le_bytes().to_vec(),
+        vec![1; 32],
+        vec![2; 32],
+        vec![3; 32],
+    ]
+    .concat()
+}
+
+pub fn execution_payload_header(extra_data: &[u8]) -> Vec<u8> {
+    // fixed part of the deneb header, extra_data is the only variable field
+    let fixed_len = 32 + 20 + 32 + 32 + 256 + 32 + 8 * 4 + 4 + 32 * 4 + 8 * 2;
+
+    [
+        vec![4; 32 + 20 + 32 + 32 + 256 + 32],
+        100u64.to_le_bytes().to_vec(),
+        30_000_000u64.to_le_bytes().to_vec(),
+        21_000u64.to_le_bytes().to_vec(),
+        1_700_000_000u64.to_le_bytes().to_vec(),
+        u32::try_from(fixed_len).unwrap().to_le_bytes().to_vec(),
+        [vec![7], vec![0; 31]].concat(),
+        vec![5; 32 * 3],
+        1u64.to_le_bytes().to_vec(),
+        2u64.to_le_bytes().to_vec(),
+        extra_data.to_vec(),
+    ]
+    .concat()
+}
+
+pub fn light_client_header(slot: u64, extra_data: &[u8]) -> Vec<u8> {
+    [
+        beacon_block_header(slot),
+        (112u32 + 4 + 32 * 4).to_le_bytes().to_vec(),
+        vec![6; 32 * 4],
+        execution_payload_header(extra_data),
+    ]
+    .concat()
+}
+
+#[test]
+fn test_ssz_bootstrap_deneb() -> TestResult {
+    let fixed_len = 4 + 48 * (SYNC_COMMITTEE_SIZE + 1) + 32 * 5;
+
+    let bytes = [
+        u32::try_from(fixed_len)?.to_le_bytes().to_vec(),
+        vec![8; 48 * (SYNC_COMMITTEE_SIZE + 1)],
+        vec![9; 32 * 5],
+        light_client_header(42, &[0xab, 0xcd]),
+    ]
+    .concat();
+
+    let bootstrap = light_client_bootstrap(&bytes, Fork::Deneb, SYNC_COMMITTEE_SIZE)?;
+
+    let header = &bootstrap["header"];
+
+    assert_eq!(header["beacon"]["slot"], "42");
+    assert_eq!(header["beacon"]["proposer_index"], "7");
+    assert_eq!(
+        header["beacon"]["body_root"],
+        format!("0x{}", "03".repeat(32))
+    );
+    assert_eq!(header["execution"]["block_number"], "100");
+    assert_eq!(header["execution"]["timestamp"], "1700000000");
+    assert_eq!(header["execution"]["base_fee_per_gas"], "7");
+    assert_eq!(header["execution"]["blob_gas_used"], "1");
+    assert_eq!(header["execution"]["excess_blob_gas"], "2");
+    assert_eq!(header["execution"]["extra_data"], "0xabcd");
+    assert_eq!(header["execution_branch"].as_array().map(Vec::len), Some(4));
+
+    assert_eq!(
+        bootstrap["current_sync_committee"]["pubkeys"]
+            .as_array()
+            .map(Vec::len),
+        Some(SYNC_COMMITTEE_SIZE)
+    );
+    assert_eq!(
+        bootstrap["current_sync_committee_branch"]
+            .as_array()
+            .map(Vec::len),
+        Some(5)
+    );
+
+    // electra has a deeper branch, so the fixed part no longer lines up
+    assert!(light_client_bootstrap(&bytes, Fork::Electra, SYNC_COMMITTEE_SIZE).is_err());
+    // no blob fields before deneb
+    assert!(light_client_bootstrap(&bytes, Fork::Capella, SYNC_COMMITTEE_SIZE).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_ssz_update_chunks() -> TestResult {
+    let chunk = |len: u64, digest: u8, payload: &[u8]| {
+        [
+            len.to_le_bytes().to_vec(),
+            vec![digest; 4],
+            payload.to_vec(),
+        ]
+        .concat()
+    };
+
+    let bytes = [chunk(7, 1, &[1, 2, 3]), chunk(5, 2, &[4])].concat();
+    let chunks = light_client_update_chunks(&bytes)?;
+
+    assert_eq!(chunks, vec![([1; 4], &[1, 2, 3][..]), ([2; 4], &[4][..])]);
+
+    // a length of the payload alone is not the spec encoding
+    let bytes = [chunk(3, 1, &[1, 2, 3]), chunk(1, 2, &[4])].concat();
+    assert!(light_client_update_chunks(&bytes).is_err());
+
+    assert!(light_client_update_chunks(&chunk(0, 1, &[])).is_err());
+
+    // a hostile length errors instead of overflowing the chunk end
+    assert!(light_client_update_chunks(&chunk(u64::MAX, 1, &[1])).is_err());
+
+    assert!(light_client_update_chunks(&[1, 2, 3]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_fork_digest_mainnet() -> TestResult {
+    let genesis_validators_root: [u8; 32] =
+        hex::decode("4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95")?
+            .try_into()
+            .map_err(|_| "genesis validators root")?;
+
+    assert_eq!(
+        fork_digest([0, 0, 0, 0], genesis_validators_root),
+        [0xb5, 0x30, 0x3f, 0x2a]
+    );
+    assert_eq!(
+        fork_digest([1, 0, 0, 0], genesis_validators_root),
+        [0xaf, 0xca, 0xab, 0xa0]
+    );
+
+    assert_eq!(Fork::from_name("Deneb")?, Fork::Deneb);
+    assert_eq!(Fork::Capella.version_key(), "CAPELLA_FORK_VERSION");
+
+    Ok(())
+}